@@ -0,0 +1,444 @@
+use std::path::PathBuf;
+
+use color_eyre::{Result, eyre::eyre};
+use gtk::{gio, glib, prelude::*, subclass::prelude::*};
+
+use nett_icon_viewer::{
+    Collections, IconSelector,
+    icon::{
+        BatchExport, DEFAULT_FILE_NAME_TEMPLATE, IconObject, PngExport, SpriteSheet,
+        expand_template, validate_template, write_sprite_sheet, write_svg_sprite,
+    },
+    icon_theme,
+};
+
+/// What the icons are exported as, in the order of the format dropdown.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum ExportFormat {
+    /// A PNG file per icon and size.
+    Png,
+    /// All icons packed into one PNG, with JSON and CSS maps of where each icon is.
+    SpriteSheet,
+    /// The SVG files of the icons as `<symbol>` elements of one SVG.
+    SvgSprite,
+}
+
+impl ExportFormat {
+    const ALL: [ExportFormat; 3] = [
+        ExportFormat::Png,
+        ExportFormat::SpriteSheet,
+        ExportFormat::SvgSprite,
+    ];
+
+    fn default_template(&self) -> &'static str {
+        match self {
+            ExportFormat::Png => DEFAULT_FILE_NAME_TEMPLATE,
+            ExportFormat::SpriteSheet => "{theme}-sprite-{size}",
+            ExportFormat::SvgSprite => "{theme}-sprite",
+        }
+    }
+}
+
+/// The size icons are looked up at to find their SVG file.
+const DEFAULT_ICON_SIZE: i32 = 128;
+
+/// Icons that can be exported, as listed in the source dropdown.
+#[derive(Debug, Default, Clone)]
+pub struct ExportSource {
+    pub label: String,
+    /// The name of the collection the icons are from, if any.
+    pub collection: Option<String>,
+    pub names: Vec<String>,
+}
+
+mod imp {
+    use std::cell::{Cell, RefCell};
+
+    use gtk::{CompositeTemplate, TemplateChild, glib::subclass::InitializingObject};
+
+    use super::*;
+
+    #[derive(CompositeTemplate, Default)]
+    #[template(resource = "/codes/blaine/NettIconViewer/batch_export.ui")]
+    pub struct BatchExportWindow {
+        #[template_child]
+        pub source_dropdown: TemplateChild<gtk::DropDown>,
+
+        #[template_child]
+        pub format_dropdown: TemplateChild<gtk::DropDown>,
+
+        #[template_child]
+        pub sizes_entry: TemplateChild<gtk::Entry>,
+
+        #[template_child]
+        pub scale_spin: TemplateChild<gtk::SpinButton>,
+
+        #[template_child]
+        pub padding_spin: TemplateChild<gtk::SpinButton>,
+
+        #[template_child]
+        pub template_entry: TemplateChild<gtk::Entry>,
+
+        #[template_child]
+        pub folder_label: TemplateChild<gtk::Label>,
+
+        #[template_child]
+        pub error_label: TemplateChild<gtk::Label>,
+
+        pub sources: RefCell<Vec<ExportSource>>,
+        format: Cell<Option<ExportFormat>>,
+        pub directory: RefCell<Option<PathBuf>>,
+        pub selector: RefCell<Option<IconSelector>>,
+    }
+
+    impl BatchExportWindow {
+        fn format(&self) -> ExportFormat {
+            ExportFormat::ALL
+                .get(self.format_dropdown.selected() as usize)
+                .copied()
+                .unwrap_or(ExportFormat::Png)
+        }
+
+        /// Switches the file name to the default of the new format, unless it was edited,
+        /// and disables the settings the format doesn't use.
+        fn format_changed(&self) {
+            let format = self.format();
+            let previous = self
+                .format
+                .replace(Some(format))
+                .unwrap_or(ExportFormat::Png);
+
+            if self.template_entry.text() == previous.default_template() {
+                self.template_entry.set_text(format.default_template());
+            }
+
+            let is_raster = format != ExportFormat::SvgSprite;
+            self.sizes_entry.set_sensitive(is_raster);
+            self.scale_spin.set_sensitive(is_raster);
+            self.padding_spin.set_sensitive(is_raster);
+        }
+
+        fn names(&self) -> Result<Vec<String>> {
+            let names = self
+                .sources
+                .borrow()
+                .get(self.source_dropdown.selected() as usize)
+                .map(|source| source.names.clone())
+                .unwrap_or_default();
+
+            if names.is_empty() {
+                return Err(eyre!("There are no icons to export"));
+            }
+
+            Ok(names)
+        }
+
+        fn directory(&self) -> Result<PathBuf> {
+            self.directory
+                .borrow()
+                .clone()
+                .ok_or_else(|| eyre!("Choose a folder to export to"))
+        }
+
+        fn png_export(&self) -> PngExport {
+            PngExport {
+                scale: self.scale_spin.value_as_int() as u32,
+                padding: self.padding_spin.value_as_int() as u32,
+                ..Default::default()
+            }
+        }
+
+        /// The path of a sprite, which is a single file, so its name can't contain `{name}`.
+        fn sprite_path(&self, size: u32, extension: &str) -> Result<PathBuf> {
+            let template = self.template_entry.text().trim().to_string();
+            validate_template(&template)?;
+
+            if template.contains("{name}") {
+                return Err(eyre!(
+                    "A sprite is a single file, its name can't use {{name}}"
+                ));
+            }
+
+            let theme = icon_theme().theme_name();
+            let file_name = expand_template(&template, "", size, &theme);
+
+            Ok(self.directory()?.join(format!("{file_name}.{extension}")))
+        }
+
+        /// Reads the export from the form, checking that it can be run.
+        fn batch_export(&self) -> Result<(Vec<String>, BatchExport)> {
+            let names = self.names()?;
+            let export = BatchExport {
+                directory: self.directory()?,
+                sizes: BatchExport::parse_sizes(&self.sizes_entry.text())?,
+                template: self.template_entry.text().trim().to_string(),
+                theme: icon_theme().theme_name().to_string(),
+                png: self.png_export(),
+            };
+            export.validate(names.len())?;
+
+            Ok((names, export))
+        }
+
+        /// Renders the sprite sheet right away and writes it in the background, showing the
+        /// outcome in the selector's status bar.
+        fn export_sprite_sheet(&self, selector: &IconSelector) -> Result<()> {
+            let names = self.names()?;
+            let [size] = BatchExport::parse_sizes(&self.sizes_entry.text())?[..] else {
+                return Err(eyre!("A sprite sheet has a single size"));
+            };
+            let path = self.sprite_path(size, "png")?;
+
+            let png = self.png_export();
+            let sheet = SpriteSheet::new(names, size, png.scale, png.padding);
+            let renderer = self
+                .obj()
+                .renderer()
+                .ok_or_else(|| eyre!("Failed to get a renderer"))?;
+            let bytes = sheet.render(&renderer, &png.palette)?;
+
+            let selector = selector.clone();
+            glib::spawn_future_local(async move {
+                let count = sheet.names.len();
+                let result = gio::spawn_blocking(move || write_sprite_sheet(&sheet, &bytes, &path))
+                    .await
+                    .expect("Failed to write sprite sheet");
+
+                selector.show_export_result(&match result {
+                    Ok(()) => format!("Exported a sprite sheet of {count} icons"),
+                    Err(err) => {
+                        log::warn!("Failed to export sprite sheet: {}", err);
+                        format!("Failed to export sprite sheet: {err}")
+                    }
+                });
+            });
+
+            Ok(())
+        }
+
+        /// Combines the SVG files of the icons in the background, skipping raster icons,
+        /// showing the outcome in the selector's status bar.
+        fn export_svg_sprite(&self, selector: &IconSelector) -> Result<()> {
+            let names = self.names()?;
+            let path = self.sprite_path(0, "svg")?;
+
+            let theme = icon_theme();
+            let icons: Vec<(String, PathBuf)> = names
+                .iter()
+                .filter_map(|name| {
+                    let file = theme
+                        .lookup_icon(
+                            name,
+                            &[],
+                            DEFAULT_ICON_SIZE,
+                            1,
+                            gtk::TextDirection::Ltr,
+                            gtk::IconLookupFlags::empty(),
+                        )
+                        .file()?
+                        .path()?;
+
+                    file.extension()
+                        .is_some_and(|ext| ext == "svg")
+                        .then(|| (name.clone(), file))
+                })
+                .collect();
+
+            if icons.is_empty() {
+                return Err(eyre!("None of the icons are scalable"));
+            }
+            let skipped = names.len() - icons.len();
+            if skipped > 0 {
+                log::debug!("Skipped {} raster icons", skipped);
+            }
+
+            let selector = selector.clone();
+            glib::spawn_future_local(async move {
+                let count = icons.len();
+                let result = gio::spawn_blocking(move || write_svg_sprite(&icons, &path))
+                    .await
+                    .expect("Failed to write SVG sprite");
+
+                selector.show_export_result(&match result {
+                    Ok(rejected) => {
+                        let mut message =
+                            format!("Exported an SVG sprite of {} icons", count - rejected.len());
+                        if skipped > 0 {
+                            message.push_str(&format!(", skipped {skipped} raster icons"));
+                        }
+                        if !rejected.is_empty() {
+                            message.push_str(&format!(
+                                ", skipped {} icons that can't be combined",
+                                rejected.len()
+                            ));
+                        }
+                        message
+                    }
+                    Err(err) => {
+                        log::warn!("Failed to export SVG sprite: {}", err);
+                        format!("Failed to export SVG sprite: {err}")
+                    }
+                });
+            });
+
+            Ok(())
+        }
+    }
+
+    #[gtk::template_callbacks]
+    impl BatchExportWindow {
+        #[template_callback]
+        fn choose_folder(&self) {
+            let dialog = gtk::FileDialog::builder().title("Export To").build();
+
+            let obj = self.obj().clone();
+            dialog.select_folder(Some(&*self.obj()), gio::Cancellable::NONE, move |file| {
+                let Some(path) = file.ok().and_then(|file| file.path()) else {
+                    return;
+                };
+
+                let imp = obj.imp();
+                imp.folder_label.set_label(&path.display().to_string());
+                imp.directory.replace(Some(path));
+            });
+        }
+
+        #[template_callback]
+        fn export(&self) {
+            let Some(selector) = self.selector.borrow().clone() else {
+                return;
+            };
+
+            let result = match self.format() {
+                ExportFormat::Png => self
+                    .batch_export()
+                    .map(|(names, export)| selector.export_icons(names, export)),
+                ExportFormat::SpriteSheet => self.export_sprite_sheet(&selector),
+                ExportFormat::SvgSprite => self.export_svg_sprite(&selector),
+            };
+
+            match result {
+                Ok(()) => self.obj().close(),
+                Err(err) => {
+                    self.error_label.set_label(&err.to_string());
+                    self.error_label.set_visible(true);
+                }
+            }
+        }
+
+        #[template_callback]
+        fn cancel(&self) {
+            self.obj().close();
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for BatchExportWindow {
+        const NAME: &'static str = "NettIconViewerBatchExportWindow";
+        type Type = super::BatchExportWindow;
+        type ParentType = gtk::Window;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_callbacks();
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for BatchExportWindow {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            self.template_entry.set_text(DEFAULT_FILE_NAME_TEMPLATE);
+
+            let obj = self.obj().clone();
+            self.format_dropdown
+                .connect_selected_notify(move |_| obj.imp().format_changed());
+        }
+    }
+
+    impl WidgetImpl for BatchExportWindow {}
+    impl WindowImpl for BatchExportWindow {}
+}
+
+glib::wrapper! {
+    pub struct BatchExportWindow(ObjectSubclass<imp::BatchExportWindow>)
+        @extends gtk::Window, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::Native,
+                    gtk::Root, gtk::ShortcutManager;
+}
+
+impl BatchExportWindow {
+    /// Creates the window, offering the selected icons, the filtered icons and every
+    /// collection for export.
+    pub fn new(
+        parent: &impl IsA<gtk::Window>,
+        selector: &IconSelector,
+        collections: &Collections,
+    ) -> Self {
+        let window: Self = glib::Object::builder().build();
+        window.set_transient_for(Some(parent));
+
+        let names = |icons: Vec<IconObject>| {
+            icons
+                .iter()
+                .map(|icon| icon.name().to_string())
+                .collect::<Vec<_>>()
+        };
+        let selected = names(selector.selected_icons());
+        let filtered = names(selector.filtered_icons());
+
+        let mut sources = vec![
+            ExportSource {
+                label: format!("Selected Icons ({})", selected.len()),
+                collection: None,
+                names: selected,
+            },
+            ExportSource {
+                label: format!("Filtered Icons ({})", filtered.len()),
+                collection: None,
+                names: filtered,
+            },
+        ];
+        sources.extend(collections.collections.iter().map(|collection| {
+            ExportSource {
+                label: format!("{} ({})", collection.name, collection.icons.len()),
+                collection: Some(collection.name.clone()),
+                names: collection
+                    .icons
+                    .iter()
+                    .map(|icon| icon.name.clone())
+                    .collect(),
+            }
+        }));
+
+        let imp = window.imp();
+        let labels: Vec<&str> = sources.iter().map(|source| source.label.as_str()).collect();
+        imp.source_dropdown
+            .set_model(Some(&gtk::StringList::new(&labels)));
+        imp.source_dropdown
+            .set_selected(if sources[0].names.is_empty() { 1 } else { 0 });
+
+        imp.sources.replace(sources);
+        imp.selector.replace(Some(selector.clone()));
+
+        window
+    }
+
+    /// Preselects the icons of the collection of the given name.
+    pub fn select_collection(&self, name: &str) {
+        let position = self
+            .imp()
+            .sources
+            .borrow()
+            .iter()
+            .position(|source| source.collection.as_deref() == Some(name));
+
+        if let Some(position) = position {
+            self.imp().source_dropdown.set_selected(position as u32);
+        }
+    }
+}
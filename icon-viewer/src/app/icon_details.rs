@@ -1,17 +1,22 @@
 use gtk::glib;
 
-use nett_icon_viewer::icon::IconObject;
+use nett_icon_viewer::{IconSelector, Snippet, Snippets};
+use nett_icon_viewer::icon::{
+    DesktopEntry, IconObject, MimeType, PngExport, RasterInfo, SizeDirectory, SvgInfo, XmlToken,
+    add_icon_drag_source, highlight_xml, read_svg,
+};
 
 use super::data_row::DataRow;
 
 const DEFAULT_ICON_SIZE: u32 = 128;
+const SIMILAR_ICON_LIMIT: usize = 24;
 
 mod imp {
     use std::cell::{Cell, RefCell};
 
     use gtk::{
         Allocation, CompositeTemplate, IconPaintable, Image, Label, ListItem, NoSelection,
-        SignalListItemFactory, StringObject, TemplateChild, Widget,
+        SignalListItemFactory, StringObject, TemplateChild, Widget, gio,
         glib::{Properties, subclass::InitializingObject},
         prelude::*,
         subclass::prelude::*,
@@ -36,12 +41,57 @@ mod imp {
         #[template_child]
         pub alias_list: TemplateChild<gtk::ListView>,
 
+        #[template_child]
+        pub duplicate_button: TemplateChild<gtk::MenuButton>,
+
+        #[template_child]
+        pub duplicate_list: TemplateChild<gtk::ListView>,
+
+        #[template_child]
+        pub similar_popover: TemplateChild<gtk::Popover>,
+
+        #[template_child]
+        pub similar_list: TemplateChild<gtk::ListView>,
+
         #[template_child]
         pub picture: TemplateChild<gtk::Picture>,
 
         #[template_child]
         pub label: TemplateChild<gtk::Label>,
 
+        #[template_child]
+        pub snippet_popover: TemplateChild<gtk::Popover>,
+
+        #[template_child]
+        pub snippet_box: TemplateChild<gtk::Box>,
+
+        #[template_child]
+        pub favorite_toggle: TemplateChild<gtk::ToggleButton>,
+
+        #[template_child]
+        pub export_popover: TemplateChild<gtk::Popover>,
+
+        #[template_child]
+        pub export_size_spin: TemplateChild<gtk::SpinButton>,
+
+        #[template_child]
+        pub export_scale_spin: TemplateChild<gtk::SpinButton>,
+
+        #[template_child]
+        pub export_padding_spin: TemplateChild<gtk::SpinButton>,
+
+        #[template_child]
+        pub export_color_button: TemplateChild<gtk::ColorDialogButton>,
+
+        #[template_child]
+        pub export_background_check: TemplateChild<gtk::CheckButton>,
+
+        #[template_child]
+        pub export_background_button: TemplateChild<gtk::ColorDialogButton>,
+
+        #[template_child]
+        pub export_original_button: TemplateChild<gtk::Button>,
+
         #[template_child]
         pub list: TemplateChild<gtk::ListBox>,
 
@@ -60,6 +110,66 @@ mod imp {
         #[template_child]
         pub symlink_path_row: TemplateChild<DataRow>,
 
+        #[template_child]
+        pub size_directory_row: TemplateChild<DataRow>,
+
+        #[template_child]
+        pub pixel_size_row: TemplateChild<DataRow>,
+
+        #[template_child]
+        pub bit_depth_row: TemplateChild<DataRow>,
+
+        #[template_child]
+        pub color_type_row: TemplateChild<DataRow>,
+
+        #[template_child]
+        pub alpha_row: TemplateChild<DataRow>,
+
+        #[template_child]
+        pub app_name_row: TemplateChild<DataRow>,
+
+        #[template_child]
+        pub generic_name_row: TemplateChild<DataRow>,
+
+        #[template_child]
+        pub app_keywords_row: TemplateChild<DataRow>,
+
+        #[template_child]
+        pub desktop_id_row: TemplateChild<DataRow>,
+
+        #[template_child]
+        pub mime_types_row: TemplateChild<DataRow>,
+
+        #[template_child]
+        pub details_stack: TemplateChild<gtk::Stack>,
+
+        #[template_child]
+        pub source_page: TemplateChild<gtk::StackPage>,
+
+        #[template_child]
+        pub view_box_row: TemplateChild<DataRow>,
+
+        #[template_child]
+        pub declared_size_row: TemplateChild<DataRow>,
+
+        #[template_child]
+        pub element_count_row: TemplateChild<DataRow>,
+
+        #[template_child]
+        pub path_count_row: TemplateChild<DataRow>,
+
+        #[template_child]
+        pub embedded_images_row: TemplateChild<DataRow>,
+
+        #[template_child]
+        pub external_references_row: TemplateChild<DataRow>,
+
+        #[template_child]
+        pub file_size_row: TemplateChild<DataRow>,
+
+        #[template_child]
+        pub source_view: TemplateChild<gtk::TextView>,
+
         #[property(get, set, construct, default = DEFAULT_ICON_SIZE)]
         pub icon_size: Cell<u32>,
 
@@ -69,8 +179,15 @@ mod imp {
         #[property(get)]
         pub paintable: RefCell<Option<IconPaintable>>,
 
+        /// The selector used to look up icons related to the current one.
+        #[property(get, set, nullable)]
+        selector: RefCell<Option<IconSelector>>,
+
         #[property(get)]
         selection: RefCell<Option<NoSelection>>,
+        duplicate_selection: RefCell<Option<NoSelection>>,
+        /// The symbolic palette of the current style, read when the export popover opens.
+        export_palette: RefCell<Vec<gtk::gdk::RGBA>>,
         bindings: RefCell<Vec<glib::Binding>>,
     }
 
@@ -88,6 +205,12 @@ mod imp {
             ));
             imp.obj().notify_paintable();
 
+            imp.export_original_button
+                .set_sensitive(icon.data().path.is_some());
+            imp.load_svg_source(icon);
+            imp.load_raster_info(icon);
+            imp.show_desktop_entries(&icon.data().desktop_entries);
+            imp.show_mime_types(&icon.data().mime_types);
             imp.stack.set_visible_child_name("details");
         } else {
             imp.unbind_icon();
@@ -139,6 +262,24 @@ mod imp {
 
             bindings.push(alias_binding);
 
+            if let Some(selection) = self.duplicate_selection.borrow().as_ref() {
+                bindings.push(
+                    icon.bind_property("duplicates", selection, "model")
+                        .transform_to(|_, v: Vec<String>| Some(gtk::StringList::from_iter(v)))
+                        .sync_create()
+                        .build(),
+                );
+            }
+
+            let duplicate_button = &self.duplicate_button.get();
+            let duplicate_binding = icon
+                .bind_property("duplicates", duplicate_button, "visible")
+                .transform_to(|_, v: Vec<String>| Some(!v.is_empty()))
+                .sync_create()
+                .build();
+
+            bindings.push(duplicate_binding);
+
             let label = &self.label.get();
             let label_binding = icon
                 .bind_property("name", label, "label")
@@ -154,6 +295,14 @@ mod imp {
 
             bindings.push(label_tooltip_binding);
 
+            let favorite_binding = icon
+                .bind_property("is-favorite", &*self.favorite_toggle, "active")
+                .bidirectional()
+                .sync_create()
+                .build();
+
+            bindings.push(favorite_binding);
+
             let symbolic_row = &self.symbolic_row.get();
             let symbolic_row_binding = icon
                 .bind_property("is-symbolic", symbolic_row, "value")
@@ -200,14 +349,227 @@ mod imp {
             bindings.push(symlink_path_binding);
         }
 
+        fn update_similar_icons(&self) {
+            let icon = self.icon.borrow();
+            let selector = self.selector.borrow();
+
+            let similar_icons = match (icon.as_ref(), selector.as_ref()) {
+                (Some(icon), Some(selector)) => selector.similar_icons(icon, SIMILAR_ICON_LIMIT),
+                _ => Vec::new(),
+            };
+
+            let store = gio::ListStore::new::<IconObject>();
+            store.extend_from_slice(&similar_icons);
+
+            self.similar_list
+                .set_model(Some(&NoSelection::new(Some(store))));
+        }
+
         fn unbind_icon(&self) {
             for binding in self.bindings.borrow_mut().drain(..) {
                 binding.unbind();
             }
         }
 
+        /// Reads the SVG source of the icon in the background and shows it in the source tab.
+        ///
+        /// The tab is hidden for icons that are not backed by an SVG file.
+        fn load_svg_source(&self, icon: &IconObject) {
+            let path = icon
+                .data()
+                .path
+                .clone()
+                .filter(|path| path.extension().is_some_and(|ext| ext == "svg"));
+
+            self.source_view.buffer().set_text("");
+            self.source_page.set_visible(path.is_some());
+
+            let Some(path) = path else {
+                self.details_stack.set_visible_child_name("properties");
+                return;
+            };
+
+            let obj = self.obj().clone();
+            let icon = icon.clone();
+            glib::spawn_future_local(async move {
+                let result = gio::spawn_blocking(move || read_svg(&path))
+                    .await
+                    .expect("Failed to read SVG source");
+
+                if obj.icon().as_ref() != Some(&icon) {
+                    return;
+                }
+
+                match result {
+                    Ok((source, info)) => obj.imp().show_svg_source(&source, &info),
+                    Err(err) => {
+                        log::warn!("Failed to inspect SVG of \"{}\": {}", icon.name(), err);
+                        obj.imp().source_page.set_visible(false);
+                    }
+                }
+            });
+        }
+
+        /// Reads the pixel information of PNG and XPM icons in the background.
+        ///
+        /// Icons whose pixel size doesn't match the size directory they're in are flagged,
+        /// since GTK scales them without any notice.
+        fn load_raster_info(&self, icon: &IconObject) {
+            let data = icon.data();
+            let size_directory = data.size_directory();
+            let path = data.path.clone().filter(|path| {
+                path.extension()
+                    .is_some_and(|ext| ext == "png" || ext == "xpm")
+            });
+            drop(data);
+
+            self.show_raster_info(size_directory.as_ref(), None);
+
+            let Some(path) = path else {
+                return;
+            };
+
+            let obj = self.obj().clone();
+            let icon = icon.clone();
+            glib::spawn_future_local(async move {
+                let result = gio::spawn_blocking(move || RasterInfo::read(&path))
+                    .await
+                    .expect("Failed to read raster info");
+
+                if obj.icon().as_ref() != Some(&icon) {
+                    return;
+                }
+
+                match result {
+                    Ok(info) => obj
+                        .imp()
+                        .show_raster_info(size_directory.as_ref(), Some(&info)),
+                    Err(err) => {
+                        log::warn!("Failed to read raster info of \"{}\": {}", icon.name(), err)
+                    }
+                }
+            });
+        }
+
+        fn show_raster_info(&self, directory: Option<&SizeDirectory>, info: Option<&RasterInfo>) {
+            let size_directory_row = self.size_directory_row.get();
+            size_directory_row.set_visible(directory.is_some());
+            size_directory_row.remove_css_class("warning");
+
+            if let Some(directory) = directory {
+                match (directory.pixel_size(), info) {
+                    (Some(size), Some(info)) if info.mismatches(directory) => {
+                        size_directory_row.set_value(format!(
+                            "{} (expected {}px, icon is {} × {}px)",
+                            directory, size, info.width, info.height
+                        ));
+                        size_directory_row.add_css_class("warning");
+                    }
+                    _ => size_directory_row.set_value(directory.to_string()),
+                }
+            }
+
+            for row in [
+                &self.pixel_size_row,
+                &self.bit_depth_row,
+                &self.color_type_row,
+                &self.alpha_row,
+            ] {
+                row.set_visible(info.is_some());
+            }
+
+            let Some(info) = info else {
+                return;
+            };
+
+            self.pixel_size_row
+                .set_value(format!("{} × {}px", info.width, info.height));
+            self.bit_depth_row.set_value(
+                info.bit_depth
+                    .map(|depth| depth.to_string())
+                    .unwrap_or_else(|| String::from("None")),
+            );
+            self.color_type_row.set_value(info.color_type.to_string());
+            self.alpha_row.set_value(info.has_alpha.to_string());
+        }
+
+        /// Shows the metadata of the applications using the icon, joined if there are several.
+        fn show_desktop_entries(&self, entries: &[DesktopEntry]) {
+            let join = |values: Vec<&str>| values.join(", ");
+
+            for (row, value) in [
+                (
+                    &self.app_name_row,
+                    join(entries.iter().filter_map(|e| e.name.as_deref()).collect()),
+                ),
+                (
+                    &self.generic_name_row,
+                    join(entries.iter().filter_map(|e| e.generic_name.as_deref()).collect()),
+                ),
+                (
+                    &self.app_keywords_row,
+                    join(entries.iter().flat_map(|e| &e.keywords).map(String::as_str).collect()),
+                ),
+                (
+                    &self.desktop_id_row,
+                    join(entries.iter().map(|e| e.id.as_str()).collect()),
+                ),
+            ] {
+                row.set_visible(!value.is_empty());
+                row.set_value(value);
+            }
+        }
+
+        /// Lists the MIME types resolving to the icon, one per line.
+        fn show_mime_types(&self, mime_types: &[MimeType]) {
+            let value = mime_types
+                .iter()
+                .map(|mime_type| match &mime_type.comment {
+                    Some(comment) => format!("{} ({})", mime_type.name, comment),
+                    None => mime_type.name.clone(),
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            self.mime_types_row.set_visible(!value.is_empty());
+            self.mime_types_row.set_value(value);
+        }
+
+        fn show_svg_source(&self, source: &str, info: &SvgInfo) {
+            let buffer = self.source_view.buffer();
+            buffer.set_text(source);
+
+            for (range, token) in highlight_xml(source) {
+                let start = buffer.iter_at_offset(range.start as i32);
+                let end = buffer.iter_at_offset(range.end as i32);
+                buffer.apply_tag_by_name(token.name(), &start, &end);
+            }
+
+            let or_none = |v: &Option<String>| v.clone().unwrap_or_else(|| String::from("None"));
+
+            self.view_box_row.set_value(or_none(&info.view_box));
+            self.declared_size_row.set_value(format!(
+                "{} × {}",
+                or_none(&info.width),
+                or_none(&info.height)
+            ));
+            self.element_count_row
+                .set_value(info.element_count.to_string());
+            self.path_count_row.set_value(info.path_count.to_string());
+            self.embedded_images_row
+                .set_value(info.embedded_images.to_string());
+            self.external_references_row
+                .set_value(if info.external_references.is_empty() {
+                    String::from("None")
+                } else {
+                    info.external_references.join(", ")
+                });
+            self.file_size_row
+                .set_value(glib::format_size(info.file_size));
+        }
+
         #[template_callback]
-        fn alias_activated(list: &gtk::ListView, index: u32) {
+        fn name_activated(list: &gtk::ListView, index: u32) {
             let model = list.model().unwrap();
             let name = model
                 .item(index)
@@ -225,6 +587,151 @@ mod imp {
             log::debug!("Copied \"{}\" to clipboard", name);
         }
 
+        /// Reads the colors symbolic icons are drawn with in the current style: foreground,
+        /// error, warning and success.
+        #[allow(deprecated)]
+        fn style_palette(&self) -> Vec<gtk::gdk::RGBA> {
+            let obj = self.obj();
+            let mut palette = PngExport::default().palette;
+            palette[0] = obj.color();
+
+            // Named colors are deprecated, but still the only way to read the theme's palette
+            let style = obj.style_context();
+            let names = ["error_color", "warning_color", "success_color"];
+            for (color, name) in palette[1..].iter_mut().zip(names) {
+                if let Some(rgba) = style.lookup_color(name) {
+                    *color = rgba;
+                }
+            }
+
+            palette
+        }
+
+        /// Collects the PNG export settings from the export popover.
+        fn png_export(&self) -> PngExport {
+            let mut export = PngExport {
+                size: self.export_size_spin.value_as_int() as u32,
+                scale: self.export_scale_spin.value_as_int() as u32,
+                padding: self.export_padding_spin.value_as_int() as u32,
+                background: self
+                    .export_background_check
+                    .is_active()
+                    .then(|| self.export_background_button.rgba()),
+                ..Default::default()
+            };
+            let palette = self.export_palette.borrow();
+            if !palette.is_empty() {
+                export.palette.clone_from(&palette);
+            }
+            export.palette[0] = self.export_color_button.rgba();
+
+            export
+        }
+
+        #[template_callback]
+        fn export_png(&self) {
+            self.export_popover.popdown();
+
+            let Some(icon) = self.icon.borrow().clone() else {
+                return;
+            };
+            let Some(renderer) = self.obj().native().and_then(|native| native.renderer()) else {
+                return;
+            };
+
+            let export = self.png_export();
+            let bytes = match export.render(&icon.name(), &renderer) {
+                Ok(bytes) => bytes,
+                Err(err) => {
+                    log::warn!("Failed to export \"{}\": {}", icon.name(), err);
+                    return;
+                }
+            };
+
+            let dialog = gtk::FileDialog::builder()
+                .title("Export PNG")
+                .initial_name(export.file_name(&icon.name()))
+                .build();
+
+            dialog.save(self.window().as_ref(), gio::Cancellable::NONE, move |file| {
+                let Some(path) = file.ok().and_then(|file| file.path()) else {
+                    return;
+                };
+
+                if let Err(err) = std::fs::write(&path, &bytes) {
+                    log::warn!("Failed to export \"{}\": {}", path.display(), err);
+                }
+            });
+        }
+
+        /// Copies the file the icon is loaded from, following symlinks.
+        #[template_callback]
+        fn export_original(&self) {
+            self.export_popover.popdown();
+
+            let Some(icon) = self.icon.borrow().clone() else {
+                return;
+            };
+            let Some(source) = icon.data().path.clone() else {
+                return;
+            };
+
+            let mut builder = gtk::FileDialog::builder().title("Export Original File");
+            if let Some(file_name) = source.file_name() {
+                builder = builder.initial_name(file_name.to_string_lossy());
+            }
+
+            builder
+                .build()
+                .save(self.window().as_ref(), gio::Cancellable::NONE, move |file| {
+                    let Some(path) = file.ok().and_then(|file| file.path()) else {
+                        return;
+                    };
+
+                    if let Err(err) = std::fs::copy(&source, &path) {
+                        log::warn!("Failed to export \"{}\": {}", path.display(), err);
+                    }
+                });
+        }
+
+        fn window(&self) -> Option<gtk::Window> {
+            self.obj().root().and_downcast()
+        }
+
+        /// Adds a button to the "Copy as…" menu for every snippet.
+        fn add_snippets(&self, snippets: Snippets) {
+            for snippet in snippets.snippets {
+                let button = gtk::Button::builder()
+                    .label(snippet.label.as_str())
+                    .tooltip_text(snippet.template.as_str())
+                    .css_classes(["flat"])
+                    .build();
+
+                if let Some(label) = button.child().and_downcast::<gtk::Label>() {
+                    label.set_xalign(0.0);
+                }
+
+                let obj = self.obj().clone();
+                button.connect_clicked(move |_| obj.imp().copy_snippet(&snippet));
+                self.snippet_box.append(&button);
+            }
+        }
+
+        fn copy_snippet(&self, snippet: &Snippet) {
+            self.snippet_popover.popdown();
+
+            let Some(icon) = self.icon.borrow().clone() else {
+                return;
+            };
+
+            let text = snippet.expand(&icon.name());
+            gtk::gdk::Display::default()
+                .expect("Failed to get display")
+                .clipboard()
+                .set_text(&text);
+            log::debug!("Copied {} snippet of \"{}\" to clipboard", snippet.label, icon.name());
+        }
+
         #[template_callback]
         fn copy_icon(&self) {
             let name = self.label.get().text();
@@ -254,52 +761,75 @@ mod imp {
                 .bind_property("paintable", &picture, "paintable")
                 .build();
 
+            let obj = self.obj().downgrade();
+            add_icon_drag_source(&picture, move || {
+                let obj = obj.upgrade()?;
+                Some((obj.icon()?, obj.icon_size()))
+            });
+
             let selection = NoSelection::new(None::<gtk::gio::ListModel>);
 
             let list = self.alias_list.get();
+            list.set_factory(Some(&name_list_factory()));
+            list.set_model(Some(&selection));
+            self.selection.borrow_mut().replace(selection);
 
-            let factory = SignalListItemFactory::new();
-            factory.connect_setup(move |_, list_item| {
-                let label = Label::builder()
-                    .ellipsize(gtk::pango::EllipsizeMode::End)
-                    .hexpand(true)
-                    .xalign(0.0)
-                    .build();
+            let duplicate_selection = NoSelection::new(None::<gtk::gio::ListModel>);
 
-                let image = Image::builder()
-                    .icon_name("edit-copy-symbolic")
-                    .icon_size(gtk::IconSize::Normal)
-                    .halign(gtk::Align::End)
-                    .build();
+            let duplicate_list = self.duplicate_list.get();
+            duplicate_list.set_factory(Some(&name_list_factory()));
+            duplicate_list.set_model(Some(&duplicate_selection));
+            self.duplicate_selection
+                .borrow_mut()
+                .replace(duplicate_selection);
 
-                let container = gtk::Box::builder()
-                    .orientation(gtk::Orientation::Horizontal)
-                    .spacing(12)
-                    .margin_start(4)
-                    .margin_end(4)
-                    .margin_top(4)
-                    .margin_bottom(4)
-                    .hexpand(true)
-                    .build();
+            let similar_list = self.similar_list.get();
+            similar_list.set_factory(Some(&similar_icon_factory()));
 
-                container.append(&label);
-                container.append(&image);
+            let obj = self.obj().clone();
+            self.similar_popover.connect_show(move |_| {
+                obj.imp().update_similar_icons();
+            });
 
-                let list_item = list_item
-                    .downcast_ref::<ListItem>()
-                    .expect("Needs to be ListItem");
+            let obj = self.obj().clone();
+            similar_list.connect_activate(move |list, index| {
+                let icon = list
+                    .model()
+                    .and_then(|model| model.item(index))
+                    .and_downcast::<IconObject>();
 
-                list_item.set_child(Some(&container));
+                obj.imp().similar_popover.popdown();
+                obj.set_icon(icon);
+            });
 
-                list_item
-                    .property_expression("item")
-                    .chain_property::<StringObject>("string")
-                    .bind(&label, "label", Widget::NONE);
+            self.add_snippets(Snippets::load().unwrap_or_else(|err| {
+                log::warn!("Failed to load snippets: {}", err);
+                Snippets::bundled()
+            }));
+
+            // The style colors are only known once the widget is styled, and change with
+            // the dark style. A color the user picked is kept.
+            let obj = self.obj().clone();
+            self.export_popover.connect_show(move |_| {
+                let imp = obj.imp();
+                let palette = imp.style_palette();
+                let synced = imp.export_palette.borrow().first().copied();
+                if synced.is_none_or(|color| color == imp.export_color_button.rgba()) {
+                    imp.export_color_button.set_rgba(&palette[0]);
+                }
+                imp.export_palette.replace(palette);
             });
 
-            list.set_factory(Some(&factory));
-            list.set_model(Some(&selection));
-            self.selection.borrow_mut().replace(selection);
+            let buffer = self.source_view.buffer();
+            for (token, color) in [
+                (XmlToken::Tag, "#1c71d8"),
+                (XmlToken::Attribute, "#c64600"),
+                (XmlToken::Value, "#26a269"),
+                (XmlToken::Comment, "#77767b"),
+                (XmlToken::Declaration, "#813d9c"),
+            ] {
+                buffer.create_tag(Some(token.name()), &[("foreground", &color)]);
+            }
         }
 
         fn dispose(&self) {
@@ -307,6 +837,88 @@ mod imp {
         }
     }
 
+    /// Creates a factory for lists of icon names that can be copied on activation.
+    fn name_list_factory() -> SignalListItemFactory {
+        let factory = SignalListItemFactory::new();
+        factory.connect_setup(move |_, list_item| {
+            let label = Label::builder()
+                .ellipsize(gtk::pango::EllipsizeMode::End)
+                .hexpand(true)
+                .xalign(0.0)
+                .build();
+
+            let image = Image::builder()
+                .icon_name("edit-copy-symbolic")
+                .icon_size(gtk::IconSize::Normal)
+                .halign(gtk::Align::End)
+                .build();
+
+            let container = gtk::Box::builder()
+                .orientation(gtk::Orientation::Horizontal)
+                .spacing(12)
+                .margin_start(4)
+                .margin_end(4)
+                .margin_top(4)
+                .margin_bottom(4)
+                .hexpand(true)
+                .build();
+
+            container.append(&label);
+            container.append(&image);
+
+            let list_item = list_item
+                .downcast_ref::<ListItem>()
+                .expect("Needs to be ListItem");
+
+            list_item.set_child(Some(&container));
+
+            list_item
+                .property_expression("item")
+                .chain_property::<StringObject>("string")
+                .bind(&label, "label", Widget::NONE);
+        });
+
+        factory
+    }
+
+    fn similar_icon_factory() -> SignalListItemFactory {
+        let factory = SignalListItemFactory::new();
+        factory.connect_setup(move |_, list_item| {
+            let image = Image::builder().pixel_size(32).build();
+            let label = Label::builder()
+                .ellipsize(gtk::pango::EllipsizeMode::End)
+                .hexpand(true)
+                .xalign(0.0)
+                .build();
+
+            let container = gtk::Box::builder()
+                .orientation(gtk::Orientation::Horizontal)
+                .spacing(8)
+                .margin_start(4)
+                .margin_end(4)
+                .margin_top(4)
+                .margin_bottom(4)
+                .build();
+
+            container.append(&image);
+            container.append(&label);
+
+            let list_item = list_item
+                .downcast_ref::<ListItem>()
+                .expect("Needs to be ListItem");
+
+            list_item.set_child(Some(&container));
+
+            let item = list_item.property_expression("item");
+            item.chain_property::<IconObject>("paintable")
+                .bind(&image, "paintable", Widget::NONE);
+            item.chain_property::<IconObject>("name")
+                .bind(&label, "label", Widget::NONE);
+        });
+
+        factory
+    }
+
     impl WidgetImpl for IconDetails {
         fn measure(&self, orientation: gtk::Orientation, for_size: i32) -> (i32, i32, i32, i32) {
             self.stack.measure(orientation, for_size)
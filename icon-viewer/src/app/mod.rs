@@ -1,6 +1,9 @@
 use gtk::{Application, CssProvider, gdk::Display, prelude::*};
+mod batch_export;
 mod data_row;
 mod icon_details;
+mod sidebar;
+mod statistics;
 mod window;
 
 pub const APP_ID: &str = "codes.blaine.NettIconViewer";
@@ -0,0 +1,561 @@
+use gtk::{glib, subclass::prelude::*};
+
+use super::batch_export::BatchExportWindow;
+use nett_icon_viewer::{
+    Collection, CollectionFormat, Collections, IconSelector, SavedSearch, SavedSearches,
+    icon_theme,
+};
+
+mod imp {
+    use std::cell::RefCell;
+
+    use gtk::{
+        Allocation, CompositeTemplate, TemplateChild, gio,
+        glib::{Properties, subclass::InitializingObject},
+        prelude::*,
+        subclass::prelude::*,
+    };
+
+    use super::*;
+
+    #[derive(CompositeTemplate, Properties, Default)]
+    #[properties(wrapper_type = super::Sidebar)]
+    #[template(resource = "/codes/blaine/NettIconViewer/sidebar.ui")]
+    pub struct Sidebar {
+        #[template_child]
+        layout: TemplateChild<gtk::Box>,
+
+        #[template_child]
+        save_popover: TemplateChild<gtk::Popover>,
+
+        #[template_child]
+        name_entry: TemplateChild<gtk::Entry>,
+
+        #[template_child]
+        saved_list: TemplateChild<gtk::ListBox>,
+
+        #[template_child]
+        collection_popover: TemplateChild<gtk::Popover>,
+
+        #[template_child]
+        collection_name_entry: TemplateChild<gtk::Entry>,
+
+        #[template_child]
+        collection_list: TemplateChild<gtk::ListBox>,
+
+        /// The selector whose search is saved and restored.
+        #[property(get, set, nullable)]
+        selector: RefCell<Option<IconSelector>>,
+
+        searches: RefCell<SavedSearches>,
+        pub collections: RefCell<Collections>,
+    }
+
+    #[gtk::template_callbacks]
+    impl Sidebar {
+        #[template_callback]
+        fn save_search(&self) {
+            let name = self.name_entry.text().trim().to_string();
+            let Some(selector) = self.selector.borrow().clone() else {
+                return;
+            };
+
+            if name.is_empty() {
+                return;
+            }
+
+            self.searches.borrow_mut().insert(SavedSearch {
+                name,
+                state: selector.search_state(),
+            });
+            self.store_searches();
+
+            self.name_entry.set_text("");
+            self.save_popover.popdown();
+        }
+
+        #[template_callback]
+        fn search_activated(&self, row: &gtk::ListBoxRow) {
+            let searches = self.searches.borrow();
+            let (Some(selector), Some(search)) = (
+                self.selector.borrow().clone(),
+                searches.searches.get(row.index() as usize),
+            ) else {
+                return;
+            };
+
+            selector.apply_search_state(&search.state);
+        }
+
+        fn remove_search(&self, name: &str) {
+            self.searches.borrow_mut().remove(name);
+            self.store_searches();
+        }
+
+        fn store_searches(&self) {
+            if let Err(err) = self.searches.borrow().save() {
+                log::warn!("Failed to save searches: {}", err);
+            }
+
+            self.update_list();
+        }
+
+        fn update_list(&self) {
+            self.saved_list.remove_all();
+
+            for search in &self.searches.borrow().searches {
+                let label = gtk::Label::builder()
+                    .label(&search.name)
+                    .xalign(0.0)
+                    .hexpand(true)
+                    .ellipsize(gtk::pango::EllipsizeMode::End)
+                    .build();
+
+                let remove = gtk::Button::builder()
+                    .icon_name("user-trash-symbolic")
+                    .tooltip_text("Delete Saved Search")
+                    .has_frame(false)
+                    .build();
+
+                let obj = self.obj().clone();
+                let name = search.name.clone();
+                remove.connect_clicked(move |_| obj.imp().remove_search(&name));
+
+                let row_box = gtk::Box::builder().spacing(4).build();
+                row_box.append(&label);
+                row_box.append(&remove);
+
+                self.saved_list.append(&row_box);
+            }
+        }
+
+        #[template_callback]
+        fn create_collection(&self) {
+            let name = self.collection_name_entry.text().trim().to_string();
+            if name.is_empty() {
+                return;
+            }
+
+            let theme = icon_theme().theme_name().to_string();
+            self.collections
+                .borrow_mut()
+                .insert(Collection::new(&name, Some(theme)));
+            self.store_collections(true);
+
+            self.collection_name_entry.set_text("");
+            self.collection_popover.popdown();
+        }
+
+        #[template_callback]
+        fn import_collection(&self) {
+            let filters = gio::ListStore::new::<gtk::FileFilter>();
+            let filter = gtk::FileFilter::new();
+            filter.set_name(Some("Collections"));
+            filter.add_suffix("toml");
+            filter.add_suffix("json");
+            filters.append(&filter);
+
+            let dialog = gtk::FileDialog::builder()
+                .title("Import Collection")
+                .filters(&filters)
+                .build();
+
+            let obj = self.obj().clone();
+            dialog.open(self.window().as_ref(), gio::Cancellable::NONE, move |file| {
+                let Some(path) = file.ok().and_then(|file| file.path()) else {
+                    return;
+                };
+
+                match Collection::import(&path) {
+                    Ok(collection) => {
+                        obj.imp().collections.borrow_mut().insert(collection);
+                        obj.imp().store_collections(true);
+                    }
+                    Err(err) => log::warn!("Failed to import \"{}\": {}", path.display(), err),
+                }
+            });
+        }
+
+        fn export_collection(&self, name: &str, format: CollectionFormat) {
+            let Some(collection) = self.collections.borrow().get(name).cloned() else {
+                return;
+            };
+
+            let dialog = gtk::FileDialog::builder()
+                .title("Export Collection")
+                .initial_name(format!("{}.{}", collection.name, format.extension()))
+                .build();
+
+            dialog.save(self.window().as_ref(), gio::Cancellable::NONE, move |file| {
+                let Some(path) = file.ok().and_then(|file| file.path()) else {
+                    return;
+                };
+
+                if let Err(err) = collection.export(&path, format) {
+                    log::warn!("Failed to export \"{}\": {}", path.display(), err);
+                }
+            });
+        }
+
+        /// Opens the batch export with the icons of the collection preselected.
+        fn export_collection_icons(&self, name: &str) {
+            let (Some(window), Some(selector)) = (self.window(), self.selector.borrow().clone())
+            else {
+                return;
+            };
+
+            let export = BatchExportWindow::new(&window, &selector, &self.collections.borrow());
+            export.select_collection(name);
+            export.present();
+        }
+
+        fn window(&self) -> Option<gtk::Window> {
+            self.obj().root().and_downcast()
+        }
+
+        /// Appends the icons selected in the selector to the collection.
+        fn add_selected_icons(&self, name: &str) {
+            let Some(selector) = self.selector.borrow().clone() else {
+                return;
+            };
+
+            if let Some(collection) = self.collections.borrow_mut().get_mut(name) {
+                for icon in selector.selected_icons() {
+                    collection.add(&icon.name());
+                }
+            }
+            self.store_collections(true);
+        }
+
+        fn rename_collection(&self, name: &str, new_name: &str) {
+            let new_name = new_name.trim();
+            if new_name.is_empty() {
+                return;
+            }
+
+            if let Err(err) = self.collections.borrow_mut().rename(name, new_name) {
+                log::warn!("Failed to rename collection: {}", err);
+                return;
+            }
+            self.store_collections(true);
+        }
+
+        fn remove_collection(&self, name: &str) {
+            self.collections.borrow_mut().remove(name);
+            self.store_collections(true);
+        }
+
+        /// Sets the note of an icon in the collection, saving the collections if it changed.
+        fn save_note(&self, name: &str, icon_name: &str, note: &str) {
+            let mut collections = self.collections.borrow_mut();
+            let Some(collection) = collections.get_mut(name) else {
+                return;
+            };
+
+            let unchanged = collection.icons.iter().any(|icon| {
+                icon.name == icon_name && icon.note.as_deref().unwrap_or_default() == note
+            });
+            if unchanged {
+                return;
+            }
+
+            collection.set_note(icon_name, note);
+            drop(collections);
+            self.store_collections(false);
+        }
+
+        /// Saves the collections, rebuilding the list if the change isn't shown already.
+        fn store_collections(&self, update_list: bool) {
+            if let Err(err) = self.collections.borrow().save() {
+                log::warn!("Failed to save collections: {}", err);
+            }
+
+            if update_list {
+                self.update_collection_list();
+            }
+        }
+
+        fn update_collection_list(&self) {
+            self.collection_list.remove_all();
+
+            for collection in &self.collections.borrow().collections {
+                let label = gtk::Label::builder()
+                    .label(&collection.name)
+                    .xalign(0.0)
+                    .hexpand(true)
+                    .ellipsize(gtk::pango::EllipsizeMode::End)
+                    .build();
+
+                let count = gtk::Label::builder()
+                    .label(collection.icons.len().to_string())
+                    .css_classes(["dim-label", "caption"])
+                    .build();
+
+                let add = gtk::Button::builder()
+                    .icon_name("list-add-symbolic")
+                    .tooltip_text("Add Selected Icons")
+                    .has_frame(false)
+                    .build();
+
+                let obj = self.obj().clone();
+                let name = collection.name.clone();
+                add.connect_clicked(move |_| obj.imp().add_selected_icons(&name));
+
+                let edit = gtk::MenuButton::builder()
+                    .icon_name("view-more-symbolic")
+                    .tooltip_text("Edit Collection")
+                    .has_frame(false)
+                    .popover(&self.collection_editor(collection, &count))
+                    .build();
+
+                let row_box = gtk::Box::builder().spacing(4).build();
+                row_box.append(&label);
+                row_box.append(&count);
+                row_box.append(&add);
+                row_box.append(&edit);
+
+                self.collection_list.append(&row_box);
+            }
+        }
+
+        /// Builds the popover to rename, export and delete a collection and edit its icons.
+        fn collection_editor(&self, collection: &Collection, count: &gtk::Label) -> gtk::Popover {
+            let layout = gtk::Box::builder()
+                .orientation(gtk::Orientation::Vertical)
+                .spacing(6)
+                .build();
+
+            let name_entry = gtk::Entry::builder().text(&collection.name).build();
+            let rename = gtk::Button::builder().label("Rename").build();
+            let rename_box = gtk::Box::builder().spacing(4).build();
+            rename_box.append(&name_entry);
+            rename_box.append(&rename);
+            layout.append(&rename_box);
+
+            let obj = self.obj().clone();
+            let name = collection.name.clone();
+            name_entry.connect_activate(move |entry| {
+                obj.imp().rename_collection(&name, &entry.text());
+            });
+
+            let obj = self.obj().clone();
+            let name = collection.name.clone();
+            let entry = name_entry.clone();
+            rename.connect_clicked(move |_| obj.imp().rename_collection(&name, &entry.text()));
+
+            if let Some(theme) = &collection.theme {
+                layout.append(
+                    &gtk::Label::builder()
+                        .label(format!("Theme: {theme}"))
+                        .xalign(0.0)
+                        .css_classes(["dim-label", "caption"])
+                        .build(),
+                );
+            }
+
+            let icon_list = gtk::ListBox::builder()
+                .selection_mode(gtk::SelectionMode::None)
+                .css_classes(["boxed-list"])
+                .build();
+            icon_list.set_placeholder(Some(
+                &gtk::Label::builder()
+                    .label("No icons yet")
+                    .margin_top(8)
+                    .margin_bottom(8)
+                    .css_classes(["dim-label"])
+                    .build(),
+            ));
+
+            for icon in &collection.icons {
+                let note = gtk::Entry::builder()
+                    .placeholder_text("Note")
+                    .text(icon.note.as_deref().unwrap_or_default())
+                    .hexpand(true)
+                    .build();
+
+                // Saved once editing is done rather than on every keystroke
+                let obj = self.obj().clone();
+                let (collection_name, icon_name) = (collection.name.clone(), icon.name.clone());
+                note.connect_activate(move |note| {
+                    obj.imp()
+                        .save_note(&collection_name, &icon_name, &note.text());
+                });
+
+                let focus = gtk::EventControllerFocus::new();
+                let obj = self.obj().clone();
+                let (collection_name, icon_name) = (collection.name.clone(), icon.name.clone());
+                focus.connect_leave(move |focus| {
+                    if let Some(note) = focus.widget().and_downcast::<gtk::Entry>() {
+                        obj.imp()
+                            .save_note(&collection_name, &icon_name, &note.text());
+                    }
+                });
+                note.add_controller(focus);
+
+                let remove = gtk::Button::builder()
+                    .icon_name("user-trash-symbolic")
+                    .tooltip_text("Remove from Collection")
+                    .has_frame(false)
+                    .build();
+
+                let row_box = gtk::Box::builder()
+                    .spacing(6)
+                    .margin_start(6)
+                    .margin_end(6)
+                    .margin_top(4)
+                    .margin_bottom(4)
+                    .build();
+                row_box.append(&gtk::Image::from_icon_name(&icon.name));
+                row_box.append(
+                    &gtk::Label::builder()
+                        .label(&icon.name)
+                        .xalign(0.0)
+                        .width_chars(16)
+                        .ellipsize(gtk::pango::EllipsizeMode::End)
+                        .build(),
+                );
+                row_box.append(&note);
+                row_box.append(&remove);
+                icon_list.append(&row_box);
+
+                let obj = self.obj().clone();
+                let (collection_name, icon_name) = (collection.name.clone(), icon.name.clone());
+                let (icon_list, count) = (icon_list.clone(), count.clone());
+                remove.connect_clicked(move |remove| {
+                    let mut collections = obj.imp().collections.borrow_mut();
+                    let Some(collection) = collections.get_mut(&collection_name) else {
+                        return;
+                    };
+
+                    collection.remove(&icon_name);
+                    count.set_label(&collection.icons.len().to_string());
+                    drop(collections);
+
+                    // The editor stays open, so only its own row is removed
+                    if let Some(row) = remove.ancestor(gtk::ListBoxRow::static_type()) {
+                        icon_list.remove(&row);
+                    }
+                    obj.imp().store_collections(false);
+                });
+            }
+
+            layout.append(
+                &gtk::ScrolledWindow::builder()
+                    .hscrollbar_policy(gtk::PolicyType::Never)
+                    .propagate_natural_height(true)
+                    .max_content_height(320)
+                    .child(&icon_list)
+                    .build(),
+            );
+
+            let actions = gtk::Box::builder().spacing(4).homogeneous(true).build();
+            for (label, format) in [
+                ("Export TOML…", CollectionFormat::Toml),
+                ("Export JSON…", CollectionFormat::Json),
+            ] {
+                let button = gtk::Button::with_label(label);
+                let obj = self.obj().clone();
+                let name = collection.name.clone();
+                button.connect_clicked(move |_| obj.imp().export_collection(&name, format));
+                actions.append(&button);
+            }
+
+            let export_icons = gtk::Button::with_label("Export Icons…");
+            let obj = self.obj().clone();
+            let name = collection.name.clone();
+            export_icons.connect_clicked(move |_| obj.imp().export_collection_icons(&name));
+            layout.append(&export_icons);
+
+            let delete = gtk::Button::builder()
+                .label("Delete")
+                .css_classes(["destructive-action"])
+                .build();
+            let obj = self.obj().clone();
+            let name = collection.name.clone();
+            delete.connect_clicked(move |_| obj.imp().remove_collection(&name));
+            actions.append(&delete);
+            layout.append(&actions);
+
+            gtk::Popover::builder().child(&layout).build()
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for Sidebar {
+        const NAME: &'static str = "NettIconViewerSidebar";
+        type Type = super::Sidebar;
+        type ParentType = gtk::Widget;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.set_css_name("sidebar");
+            klass.bind_template();
+            klass.bind_template_callbacks();
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    #[glib::derived_properties]
+    impl ObjectImpl for Sidebar {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            match SavedSearches::load() {
+                Ok(searches) => {
+                    self.searches.replace(searches);
+                }
+                Err(err) => log::warn!("Failed to load saved searches: {}", err),
+            }
+
+            self.update_list();
+
+            match Collections::load() {
+                Ok(collections) => {
+                    self.collections.replace(collections);
+                }
+                Err(err) => log::warn!("Failed to load collections: {}", err),
+            }
+
+            self.update_collection_list();
+        }
+
+        fn dispose(&self) {
+            self.layout.unparent();
+        }
+    }
+
+    impl WidgetImpl for Sidebar {
+        fn measure(&self, orientation: gtk::Orientation, for_size: i32) -> (i32, i32, i32, i32) {
+            self.layout.measure(orientation, for_size)
+        }
+        fn size_allocate(&self, width: i32, height: i32, baseline: i32) {
+            self.parent_size_allocate(width, height, baseline);
+            self.layout
+                .size_allocate(&Allocation::new(0, 0, width, height), baseline);
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct Sidebar(ObjectSubclass<imp::Sidebar>)
+        @extends gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl Sidebar {
+    pub fn new() -> Self {
+        glib::Object::builder().build()
+    }
+
+    pub fn collections(&self) -> Collections {
+        self.imp().collections.borrow().clone()
+    }
+}
+
+impl Default for Sidebar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,155 @@
+use gtk::{gio, glib, prelude::*, subclass::prelude::*};
+
+use nett_icon_viewer::{ThemeStatistics, icon::IconData};
+
+use super::data_row::DataRow;
+
+mod imp {
+    use gtk::{CompositeTemplate, TemplateChild, glib::subclass::InitializingObject};
+
+    use super::*;
+
+    #[derive(CompositeTemplate, Default)]
+    #[template(resource = "/codes/blaine/NettIconViewer/statistics.ui")]
+    pub struct StatisticsWindow {
+        #[template_child]
+        pub stack: TemplateChild<gtk::Stack>,
+
+        #[template_child]
+        pub content: TemplateChild<gtk::Box>,
+    }
+
+    impl StatisticsWindow {
+        pub fn show_statistics(&self, stats: &ThemeStatistics) {
+            let percentage = |count: usize| {
+                if stats.total == 0 {
+                    0.0
+                } else {
+                    count as f64 / stats.total as f64 * 100.0
+                }
+            };
+
+            let count_row = |title: &str, count: usize| {
+                (
+                    title.to_string(),
+                    format!("{} ({:.1}%)", count, percentage(count)),
+                )
+            };
+
+            self.add_section(
+                "Overview",
+                vec![
+                    (String::from("Icons"), stats.total.to_string()),
+                    count_row("Symlinks", stats.symlinks),
+                    count_row("Broken Symlinks", stats.broken_symlinks),
+                    count_row("Symbolic", stats.symbolic),
+                    count_row("Embedded", stats.embedded),
+                    count_row("Scalable", stats.scalable),
+                    (
+                        String::from("Disk Usage"),
+                        glib::format_size(stats.disk_usage).to_string(),
+                    ),
+                ],
+            );
+
+            for (title, counts) in [
+                ("Contexts", &stats.contexts),
+                ("Size Directories", &stats.size_directories),
+                ("File Formats", &stats.formats),
+            ] {
+                self.add_section(
+                    title,
+                    counts
+                        .iter()
+                        .map(|(name, count)| count_row(name, *count))
+                        .collect(),
+                );
+            }
+
+            self.add_section(
+                "Largest Files",
+                stats
+                    .largest_files
+                    .iter()
+                    .map(|(name, size)| (name.clone(), glib::format_size(*size).to_string()))
+                    .collect(),
+            );
+
+            self.stack.set_visible_child_name("statistics");
+        }
+
+        fn add_section(&self, title: &str, rows: Vec<(String, String)>) {
+            let label = gtk::Label::builder()
+                .label(title)
+                .xalign(0.0)
+                .margin_top(8)
+                .css_classes(["heading"])
+                .build();
+
+            let list = gtk::ListBox::builder()
+                .selection_mode(gtk::SelectionMode::None)
+                .css_classes(["boxed-list", "rich-list"])
+                .build();
+
+            for (title, value) in rows {
+                let row: DataRow = glib::Object::builder()
+                    .property("title", title)
+                    .property("value", value)
+                    .property("activatable", false)
+                    .build();
+
+                list.append(&row);
+            }
+
+            self.content.append(&label);
+            self.content.append(&list);
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for StatisticsWindow {
+        const NAME: &'static str = "NettIconViewerStatisticsWindow";
+        type Type = super::StatisticsWindow;
+        type ParentType = gtk::Window;
+
+        fn class_init(klass: &mut Self::Class) {
+            DataRow::ensure_type();
+
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for StatisticsWindow {}
+    impl WidgetImpl for StatisticsWindow {}
+    impl WindowImpl for StatisticsWindow {}
+}
+
+glib::wrapper! {
+    pub struct StatisticsWindow(ObjectSubclass<imp::StatisticsWindow>)
+        @extends gtk::Window, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::Native,
+                    gtk::Root, gtk::ShortcutManager;
+}
+
+impl StatisticsWindow {
+    /// Creates the window and collects the statistics of the given icons in the background.
+    pub fn new(parent: &impl IsA<gtk::Window>, icons: Vec<IconData>) -> Self {
+        let window: Self = glib::Object::builder().build();
+        window.set_transient_for(Some(parent));
+
+        let obj = window.clone();
+        glib::spawn_future_local(async move {
+            let stats = gio::spawn_blocking(move || ThemeStatistics::collect(&icons))
+                .await
+                .expect("Failed to collect statistics");
+
+            obj.imp().show_statistics(&stats);
+        });
+
+        window
+    }
+}
@@ -1,4 +1,7 @@
+use super::batch_export::BatchExportWindow;
 use super::icon_details::IconDetails;
+use super::sidebar::Sidebar;
+use super::statistics::StatisticsWindow;
 use gtk::prelude::*;
 use gtk::{gio, glib};
 use nett_icon_viewer::IconSelector;
@@ -30,6 +33,8 @@ mod imp {
         pub icon_details: TemplateChild<IconDetails>,
         #[template_child]
         pub paned: TemplateChild<gtk::Paned>,
+        #[template_child]
+        pub sidebar: TemplateChild<Sidebar>,
 
         #[property(get)]
         split_percentage: Cell<f64>,
@@ -40,7 +45,8 @@ mod imp {
         fn calculate_paned_position(&self) {
             let paned = self.paned.get();
             let percentage = self.split_percentage.get();
-            let width = self.obj().width() as f64;
+            // The sidebar takes up part of the window when it's revealed
+            let width = paned.width() as f64;
 
             self.block_handler();
             paned.set_position((width * percentage) as i32);
@@ -67,6 +73,22 @@ mod imp {
         }
     }
 
+    #[gtk::template_callbacks]
+    impl Window {
+        #[template_callback]
+        fn show_statistics(&self) {
+            let window = StatisticsWindow::new(&*self.obj(), self.view.icon_data());
+            window.present();
+        }
+
+        #[template_callback]
+        fn show_batch_export(&self) {
+            let window =
+                BatchExportWindow::new(&*self.obj(), &self.view, &self.sidebar.collections());
+            window.present();
+        }
+    }
+
     #[glib::object_subclass]
     impl ObjectSubclass for Window {
         const NAME: &'static str = "NettIconViewerWindow";
@@ -75,6 +97,7 @@ mod imp {
 
         fn class_init(klass: &mut Self::Class) {
             klass.bind_template();
+            klass.bind_template_callbacks();
         }
 
         fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
@@ -125,6 +148,13 @@ mod imp {
             // TODO: Add ability to save on split percentage on exit.
             self.split_percentage.set(0.65);
 
+            let details = self.icon_details.get();
+            self.view.connect_selected_notify(move |view| {
+                if let Some(icon) = view.selected_icon() {
+                    details.set_icon(Some(icon));
+                }
+            });
+
             let details = self.icon_details.get();
             self.view.connect_activate(move |view, index| {
                 if let Some(icon) = view
@@ -0,0 +1,240 @@
+use std::path::Path;
+
+use color_eyre::{Result, eyre::eyre};
+use serde::{Deserialize, Serialize};
+
+use super::{load_config, save_config};
+
+const COLLECTIONS_FILE: &str = "collections.toml";
+
+/// The file formats collections can be imported from and exported to.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum CollectionFormat {
+    Toml,
+    Json,
+}
+
+impl CollectionFormat {
+    /// Picks the format by the extension of the path.
+    pub fn from_path(path: &Path) -> Result<Self> {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Ok(CollectionFormat::Toml),
+            Some("json") => Ok(CollectionFormat::Json),
+            _ => Err(eyre!("Unsupported collection file: \"{}\"", path.display())),
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            CollectionFormat::Toml => "toml",
+            CollectionFormat::Json => "json",
+        }
+    }
+}
+
+/// An icon of a [Collection], with an optional note on what it's used for.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CollectionIcon {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+/// A named, ordered list of icons, e.g. the icons of a toolbar.
+///
+/// Exported collections double as manifests of the icons an app uses.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Collection {
+    pub name: String,
+    /// The icon theme the collection is meant for.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+    #[serde(default)]
+    pub icons: Vec<CollectionIcon>,
+}
+
+impl Collection {
+    pub fn new(name: &str, theme: Option<String>) -> Self {
+        Collection {
+            name: name.to_string(),
+            theme,
+            icons: Vec::new(),
+        }
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.icons.iter().any(|icon| icon.name == name)
+    }
+
+    /// Appends the icon, unless it's already part of the collection.
+    pub fn add(&mut self, name: &str) {
+        if !self.contains(name) {
+            self.icons.push(CollectionIcon {
+                name: name.to_string(),
+                note: None,
+            });
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        self.icons.retain(|icon| icon.name != name);
+    }
+
+    /// Sets the note of the icon, an empty note removes it.
+    pub fn set_note(&mut self, name: &str, note: &str) {
+        if let Some(icon) = self.icons.iter_mut().find(|icon| icon.name == name) {
+            icon.note = (!note.is_empty()).then(|| note.to_string());
+        }
+    }
+
+    pub fn parse(source: &str, format: CollectionFormat) -> Result<Self> {
+        Ok(match format {
+            CollectionFormat::Toml => toml::from_str(source)?,
+            CollectionFormat::Json => serde_json::from_str(source)?,
+        })
+    }
+
+    pub fn to_string(&self, format: CollectionFormat) -> Result<String> {
+        Ok(match format {
+            CollectionFormat::Toml => toml::to_string_pretty(self)?,
+            CollectionFormat::Json => serde_json::to_string_pretty(self)? + "\n",
+        })
+    }
+
+    /// Reads a collection from a TOML or JSON file.
+    pub fn import(path: &Path) -> Result<Self> {
+        Self::parse(
+            &std::fs::read_to_string(path)?,
+            CollectionFormat::from_path(path)?,
+        )
+    }
+
+    /// Writes the collection to a file in the format, whatever the extension of the path.
+    pub fn export(&self, path: &Path, format: CollectionFormat) -> Result<()> {
+        std::fs::write(path, self.to_string(format)?)?;
+        Ok(())
+    }
+}
+
+/// The collections, stored as TOML in the config directory.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Collections {
+    #[serde(default, rename = "collection")]
+    pub collections: Vec<Collection>,
+}
+
+impl Collections {
+    /// Loads the collections, empty if none were saved yet.
+    pub fn load() -> Result<Self> {
+        Ok(load_config(COLLECTIONS_FILE)?.unwrap_or_default())
+    }
+
+    pub fn parse(source: &str) -> Result<Self> {
+        Ok(toml::from_str(source)?)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        save_config(COLLECTIONS_FILE, self)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Collection> {
+        self.collections.iter().find(|c| c.name == name)
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Collection> {
+        self.collections.iter_mut().find(|c| c.name == name)
+    }
+
+    /// Adds the collection, renaming it with a number suffix if the name is taken.
+    ///
+    /// Returns the name the collection was added with.
+    pub fn insert(&mut self, mut collection: Collection) -> String {
+        let base = collection.name.clone();
+        let mut suffix = 2;
+        while self.get(&collection.name).is_some() {
+            collection.name = format!("{base} {suffix}");
+            suffix += 1;
+        }
+
+        let name = collection.name.clone();
+        self.collections.push(collection);
+        name
+    }
+
+    pub fn rename(&mut self, name: &str, new_name: &str) -> Result<()> {
+        if name != new_name && self.get(new_name).is_some() {
+            return Err(eyre!("A collection named \"{}\" already exists", new_name));
+        }
+
+        let collection = self
+            .get_mut(name)
+            .ok_or_else(|| eyre!("No collection named \"{}\"", name))?;
+        collection.name = new_name.to_string();
+        Ok(())
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        self.collections.retain(|c| c.name != name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_formats() {
+        let mut collection = Collection::new("Toolbar", Some(String::from("Adwaita")));
+        collection.add("document-open-symbolic");
+        collection.add("document-save-symbolic");
+        collection.add("document-open-symbolic");
+        collection.set_note("document-save-symbolic", "Save button");
+
+        for format in [CollectionFormat::Toml, CollectionFormat::Json] {
+            let source = collection.to_string(format).unwrap();
+            assert_eq!(Collection::parse(&source, format).unwrap(), collection);
+        }
+
+        let source = collection.to_string(CollectionFormat::Toml).unwrap();
+        assert!(source.contains("[[icons]]"));
+        assert_eq!(source.matches("note =").count(), 1);
+
+        let json = r#"{"name": "Settings", "icons": [{"name": "emblem-system"}]}"#;
+        let imported = Collection::parse(json, CollectionFormat::Json).unwrap();
+        assert_eq!(imported.theme, None);
+        assert_eq!(imported.icons[0].name, "emblem-system");
+
+        assert_eq!(
+            CollectionFormat::from_path(Path::new("icons.json")).unwrap(),
+            CollectionFormat::Json
+        );
+        assert!(CollectionFormat::from_path(Path::new("icons.yaml")).is_err());
+    }
+
+    #[test]
+    fn test_collections() {
+        let mut collections = Collections::default();
+        assert_eq!(
+            collections.insert(Collection::new("Toolbar", None)),
+            "Toolbar"
+        );
+        assert_eq!(
+            collections.insert(Collection::new("Toolbar", None)),
+            "Toolbar 2"
+        );
+
+        assert!(collections.rename("Toolbar 2", "Toolbar").is_err());
+        collections.rename("Toolbar 2", "Settings").unwrap();
+        collections
+            .get_mut("Settings")
+            .unwrap()
+            .add("emblem-system");
+
+        let source = toml::to_string_pretty(&collections).unwrap();
+        assert!(source.contains("[[collection]]"));
+        assert_eq!(Collections::parse(&source).unwrap(), collections);
+
+        collections.remove("Toolbar");
+        assert_eq!(collections.collections.len(), 1);
+    }
+}
@@ -0,0 +1,65 @@
+use std::collections::BTreeSet;
+
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+
+use super::{load_config, save_config};
+
+const FAVORITES_FILE: &str = "favorites.toml";
+
+/// Names of the favorite icons, stored as TOML in the config directory.
+///
+/// Favorites are stored by name rather than by file, so they apply to every theme.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Favorites {
+    #[serde(default)]
+    pub names: BTreeSet<String>,
+}
+
+impl Favorites {
+    /// Loads the favorites, empty if none were saved yet.
+    pub fn load() -> Result<Self> {
+        Ok(load_config(FAVORITES_FILE)?.unwrap_or_default())
+    }
+
+    pub fn parse(source: &str) -> Result<Self> {
+        Ok(toml::from_str(source)?)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        save_config(FAVORITES_FILE, self)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.names.contains(name)
+    }
+
+    /// Adds or removes the icon, returning whether anything changed.
+    pub fn set(&mut self, name: &str, favorite: bool) -> bool {
+        if favorite {
+            self.names.insert(name.to_string())
+        } else {
+            self.names.remove(name)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let mut favorites = Favorites::default();
+        assert!(favorites.set("user-trash", true));
+        assert!(favorites.set("edit-copy", true));
+        assert!(!favorites.set("edit-copy", true));
+
+        let source = toml::to_string_pretty(&favorites).unwrap();
+        assert_eq!(Favorites::parse(&source).unwrap(), favorites);
+
+        assert!(favorites.set("user-trash", false));
+        assert!(!favorites.contains("user-trash") && favorites.contains("edit-copy"));
+        assert!(Favorites::parse("").unwrap().names.is_empty());
+    }
+}
@@ -1,8 +1,13 @@
 use gtk::glib;
 use gtk::glib::subclass::prelude::*;
+use gtk::prelude::*;
+use serde::{Deserialize, Serialize};
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Default, glib::Enum)]
+#[derive(
+    Copy, Clone, Debug, Eq, PartialEq, Hash, Default, glib::Enum, Serialize, Deserialize,
+)]
 #[enum_type(name = "NettIconViewerFilterDisplayMode")]
+#[serde(rename_all = "lowercase")]
 pub enum FilterMode {
     #[default]
     Is,
@@ -10,7 +15,7 @@ pub enum FilterMode {
     Either,
 }
 
-use super::CATEGORIES;
+use super::{CATEGORIES, FORMATS};
 
 mod imp {
     use std::cell::{Cell, RefCell};
@@ -36,6 +41,12 @@ mod imp {
         #[template_child]
         category_box: TemplateChild<gtk::Box>,
 
+        #[template_child]
+        format_box: TemplateChild<gtk::Box>,
+
+        #[template_child]
+        size_directory_box: TemplateChild<gtk::Box>,
+
         #[template_child]
         symbolic_check: TemplateChild<gtk::CheckButton>,
 
@@ -45,6 +56,12 @@ mod imp {
         #[template_child]
         embedded_check: TemplateChild<gtk::CheckButton>,
 
+        #[template_child]
+        duplicate_check: TemplateChild<gtk::CheckButton>,
+
+        #[template_child]
+        favorite_check: TemplateChild<gtk::CheckButton>,
+
         #[template_child]
         invalid_symlink_check: TemplateChild<gtk::CheckButton>,
 
@@ -60,8 +77,23 @@ mod imp {
         #[property(get, set = set_embedded_filter_mode, construct, builder(FilterMode::Either))]
         pub embedded_filter_mode: RefCell<FilterMode>,
 
+        #[property(get, set = set_duplicate_filter_mode, construct, builder(FilterMode::Either))]
+        pub duplicate_filter_mode: RefCell<FilterMode>,
+
+        #[property(get, set = set_favorite_filter_mode, construct, builder(FilterMode::Either))]
+        pub favorite_filter_mode: RefCell<FilterMode>,
+
         #[property(get, set = set_included_categories)]
         pub included_categories: RefCell<Vec<String>>,
+
+        #[property(get, set = set_included_formats)]
+        pub included_formats: RefCell<Vec<String>>,
+
+        #[property(get, set = set_included_size_directories)]
+        pub included_size_directories: RefCell<Vec<String>>,
+
+        /// Values of the size directories the theme contains.
+        pub size_directories: RefCell<Vec<String>>,
     }
 
     fn set_symlink_filter_mode(imp: &FilterWidget, mode: FilterMode) {
@@ -85,6 +117,20 @@ mod imp {
         imp.obj().notify_symbolic_filter_mode();
     }
 
+    fn set_duplicate_filter_mode(imp: &FilterWidget, mode: FilterMode) {
+        map_filter_mode_to_check(&imp.duplicate_check, &mode);
+
+        *imp.duplicate_filter_mode.borrow_mut() = mode;
+        imp.obj().notify_duplicate_filter_mode();
+    }
+
+    fn set_favorite_filter_mode(imp: &FilterWidget, mode: FilterMode) {
+        map_filter_mode_to_check(&imp.favorite_check, &mode);
+
+        *imp.favorite_filter_mode.borrow_mut() = mode;
+        imp.obj().notify_favorite_filter_mode();
+    }
+
     fn set_included_categories(imp: &FilterWidget, included_categories: Vec<String>) {
         let included_categories_set: HashSet<_> = HashSet::from_iter(included_categories);
 
@@ -94,6 +140,25 @@ mod imp {
         imp.obj().notify_included_categories();
     }
 
+    fn set_included_formats(imp: &FilterWidget, included_formats: Vec<String>) {
+        let included_formats_set: HashSet<_> = HashSet::from_iter(included_formats);
+
+        imp.included_formats
+            .replace(included_formats_set.into_iter().collect());
+
+        imp.obj().notify_included_formats();
+    }
+
+    fn set_included_size_directories(imp: &FilterWidget, included_size_directories: Vec<String>) {
+        let included_size_directories_set: HashSet<_> =
+            HashSet::from_iter(included_size_directories);
+
+        imp.included_size_directories
+            .replace(included_size_directories_set.into_iter().collect());
+
+        imp.obj().notify_included_size_directories();
+    }
+
     #[gtk::template_callbacks]
     impl FilterWidget {
         #[template_callback]
@@ -135,18 +200,78 @@ mod imp {
             obj.set_symlink_filter_mode(new_mode);
         }
 
-        fn remove_category(&self, category: &str) {
-            let mut included_categories = self.included_categories.borrow_mut().clone();
-            included_categories.retain(|c| c != category);
+        #[template_callback]
+        fn duplicate_toggled(&self) {
+            let obj = self.obj();
 
-            set_included_categories(self, included_categories);
+            let new_mode = match obj.duplicate_filter_mode() {
+                FilterMode::Is => FilterMode::Not,
+                FilterMode::Not => FilterMode::Either,
+                FilterMode::Either => FilterMode::Is,
+            };
+
+            obj.set_duplicate_filter_mode(new_mode);
         }
 
-        fn add_category(&self, category: &str) {
-            let mut included_categories = self.included_categories.borrow_mut().clone();
-            included_categories.push(category.to_string());
+        #[template_callback]
+        fn favorite_toggled(&self) {
+            let obj = self.obj();
+
+            let new_mode = match obj.favorite_filter_mode() {
+                FilterMode::Is => FilterMode::Not,
+                FilterMode::Not => FilterMode::Either,
+                FilterMode::Either => FilterMode::Is,
+            };
 
-            set_included_categories(self, included_categories);
+            obj.set_favorite_filter_mode(new_mode);
+        }
+
+        /// Adds a check button for every `(label, value)` pair to the container.
+        ///
+        /// The buttons are kept in sync with the given list property, which holds the
+        /// values that are checked.
+        pub fn add_value_checks(
+            &self,
+            container: &gtk::Box,
+            property: &'static str,
+            values: &[(String, String)],
+        ) {
+            for (label, value) in values {
+                let check = gtk::CheckButton::builder()
+                    .label(label)
+                    .active(true)
+                    .build();
+
+                let obj = self.obj().clone();
+                let included = value.clone();
+                obj.bind_property(property, &check, "active")
+                    .transform_to(move |_, v: Vec<String>| Some(v.contains(&included)))
+                    .sync_create()
+                    .build();
+
+                let value = value.clone();
+                check.connect_toggled(move |check| {
+                    obj.imp().toggle_value(property, &value, check.is_active());
+                });
+
+                container.append(&check);
+            }
+        }
+
+        fn toggle_value(&self, property: &str, value: &str, included: bool) {
+            let obj = self.obj();
+            let mut values: Vec<String> = obj.property(property);
+
+            if values.iter().any(|v| v == value) == included {
+                return;
+            }
+
+            values.retain(|v| v != value);
+            if included {
+                values.push(value.to_string());
+            }
+
+            obj.set_property(property, values);
         }
     }
 
@@ -185,32 +310,25 @@ mod imp {
 
             map_filter_mode_to_check(&self.symbolic_check, &obj.symbolic_filter_mode());
             map_filter_mode_to_check(&self.symlink_check, &obj.symlink_filter_mode());
-
-            for (name, value) in CATEGORIES.iter().chain(&[("Unknown", "unknown")]) {
-                let check = gtk::CheckButton::builder()
-                    .label(*name)
-                    .active(true)
-                    .build();
-                self.included_categories
-                    .borrow_mut()
-                    .push(value.to_string());
-
-                let obj = self.obj().clone();
-                obj.bind_property("included-categories", &check, "active")
-                    .transform_to(move |_, v: Vec<String>| Some(v.contains(&value.to_string())))
-                    .build();
-
-                let obj = self.obj().clone();
-                check.connect_toggled(move |check| {
-                    if check.is_active() {
-                        obj.imp().add_category(value);
-                    } else {
-                        obj.imp().remove_category(value);
-                    }
-                });
-
-                self.category_box.append(&check);
-            }
+            map_filter_mode_to_check(&self.duplicate_check, &obj.duplicate_filter_mode());
+            map_filter_mode_to_check(&self.favorite_check, &obj.favorite_filter_mode());
+
+            let categories: Vec<_> = CATEGORIES
+                .iter()
+                .chain(&[("Unknown", "unknown")])
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect();
+            self.included_categories
+                .replace(categories.iter().map(|(_, value)| value.clone()).collect());
+            self.add_value_checks(&self.category_box, "included-categories", &categories);
+
+            let formats: Vec<_> = FORMATS
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect();
+            self.included_formats
+                .replace(formats.iter().map(|(_, value)| value.clone()).collect());
+            self.add_value_checks(&self.format_box, "included-formats", &formats);
         }
 
         fn dispose(&self) {
@@ -239,6 +357,36 @@ impl FilterWidget {
     pub fn new() -> Self {
         glib::Object::builder().build()
     }
+
+    /// Replaces the size directories that can be filtered by with `(label, value)` pairs,
+    /// including all of them.
+    pub fn set_size_directories(&self, directories: Vec<(String, String)>) {
+        let imp = self.imp();
+
+        while let Some(child) = imp.size_directory_box.first_child() {
+            imp.size_directory_box.remove(&child);
+        }
+
+        let values: Vec<_> = directories.iter().map(|(_, value)| value.clone()).collect();
+        imp.size_directories.replace(values.clone());
+        self.set_included_size_directories(values);
+
+        imp.add_value_checks(
+            &imp.size_directory_box,
+            "included-size-directories",
+            &directories,
+        );
+    }
+
+    /// Values of the size directories the theme contains.
+    pub fn size_directories(&self) -> Vec<String> {
+        self.imp().size_directories.borrow().clone()
+    }
+
+    /// Whether any of the theme's size directories is filtered out.
+    pub fn filters_size_directories(&self) -> bool {
+        self.imp().size_directories.borrow().len() != self.included_size_directories().len()
+    }
 }
 
 impl Default for FilterWidget {
@@ -1,10 +1,9 @@
-use fuzzy_matcher::FuzzyMatcher;
-use fuzzy_matcher::skim::SkimMatcherV2;
 use gtk::glib;
 use gtk::glib::object::ObjectExt;
 use gtk::glib::subclass::prelude::*;
 
-use super::IconObject;
+use super::{IconObject, add_icon_drag_source};
+use crate::TextMatcher;
 
 mod imp {
     use std::cell::{Cell, RefCell};
@@ -28,15 +27,21 @@ mod imp {
         #[template_child]
         pub count: TemplateChild<gtk::Label>,
         #[template_child]
+        pub favorite_toggle: TemplateChild<gtk::ToggleButton>,
+        #[template_child]
         pub container: TemplateChild<gtk::Grid>,
         #[template_child]
         pub label: TemplateChild<gtk::Label>,
         #[template_child]
+        pub alias_label: TemplateChild<gtk::Label>,
+        #[template_child]
         pub image: TemplateChild<gtk::Picture>,
 
         #[property(get, set)]
         pub icon_size: Cell<u32>,
 
+        /// The icon the cell is bound to, offered when the cell is dragged.
+        pub icon: RefCell<Option<IconObject>>,
         pub bindings: RefCell<Vec<glib::Binding>>,
     }
 
@@ -69,6 +74,13 @@ mod imp {
             let _ = outer
                 .bind_property("icon-size", &target, "height-request")
                 .build();
+
+            let obj = self.obj().downgrade();
+            add_icon_drag_source(&*self.obj(), move || {
+                let obj = obj.upgrade()?;
+                let icon = obj.imp().icon.borrow().clone()?;
+                Some((icon, obj.icon_size()))
+            });
         }
 
         fn dispose(&self) {
@@ -98,10 +110,11 @@ impl IconWidget {
         glib::Object::builder().build()
     }
 
-    pub fn bind(&self, icon: &IconObject, search_text: &str, icon_size: u32) {
+    pub fn bind(&self, icon: &IconObject, matcher: &TextMatcher, icon_size: u32) {
         let image = self.imp().image.clone();
         let label = self.imp().label.clone();
         let mut bindings = self.imp().bindings.borrow_mut();
+        self.imp().icon.replace(Some(icon.clone()));
 
         if icon_size != self.icon_size() {
             self.set_icon_size(icon_size);
@@ -144,6 +157,14 @@ impl IconWidget {
 
         bindings.push(count_visibility_binding);
 
+        let favorite_binding = icon
+            .bind_property("is-favorite", &*self.imp().favorite_toggle, "active")
+            .bidirectional()
+            .sync_create()
+            .build();
+
+        bindings.push(favorite_binding);
+
         let icon_size_binding = self
             .bind_property("icon-size", icon, "icon-size")
             .sync_create()
@@ -152,26 +173,32 @@ impl IconWidget {
         bindings.push(icon_size_binding);
 
         let text = label.text().to_string();
-        let matcher = SkimMatcherV2::default();
+        let alias_label = self.imp().alias_label.get();
 
-        if search_text.is_empty() {
+        if matcher.is_empty() {
             label.set_markup(&glib::markup_escape_text(&text));
             return;
         }
 
-        // Fuzzy match positions
-        if let Some((_, indices)) = matcher.fuzzy_indices(&text, search_text) {
-            let mut markup = String::new();
-            for (i, c) in text.chars().enumerate() {
-                if indices.contains(&i) {
-                    markup.push_str(&format!("<span background='#99009955'><b>{}</b></span>", c));
-                } else {
-                    markup.push(c);
-                }
-            }
-            label.set_markup(&markup);
-        } else {
-            label.set_markup(&glib::markup_escape_text(&text));
+        // Match positions of the current search mode
+        let name_indices = matcher.indices(&text);
+        match &name_indices {
+            Some(indices) => label.set_markup(&highlight_markup(&text, indices)),
+            None => label.set_markup(&glib::markup_escape_text(&text)),
+        }
+
+        let aliases = icon.aliases();
+        let Some((alias, _)) = matcher.best_match(aliases.iter().map(String::as_str)) else {
+            return;
+        };
+
+        count.add_css_class("alias-match");
+
+        // Only point out the alias if it's the reason the icon is displayed
+        if name_indices.is_none() {
+            let indices = matcher.indices(alias).unwrap_or_default();
+            alias_label.set_markup(&highlight_markup(alias, &indices));
+            alias_label.set_visible(true);
         }
     }
 
@@ -179,11 +206,33 @@ impl IconWidget {
         for binding in self.imp().bindings.borrow_mut().drain(..) {
             binding.unbind();
         }
+        self.imp().icon.replace(None);
 
         self.imp().label.set_markup("");
+        self.imp().alias_label.set_markup("");
+        self.imp().alias_label.set_visible(false);
+        self.imp().count.remove_css_class("alias-match");
     }
 }
 
+/// Escapes the text for use as markup, highlighting the chars at the given indices.
+fn highlight_markup(text: &str, indices: &[usize]) -> String {
+    let mut markup = String::new();
+    for (i, c) in text.chars().enumerate() {
+        let escaped = glib::markup_escape_text(c.encode_utf8(&mut [0; 4]));
+        if indices.contains(&i) {
+            markup.push_str(&format!(
+                "<span background='#99009955'><b>{}</b></span>",
+                escaped
+            ));
+        } else {
+            markup.push_str(&escaped);
+        }
+    }
+
+    markup
+}
+
 impl Default for IconWidget {
     fn default() -> Self {
         Self::new()
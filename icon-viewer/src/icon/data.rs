@@ -1,17 +1,43 @@
 use gtk::{IconPaintable, glib, prelude::*, subclass::prelude::*};
-use std::{cell::Ref, collections::HashSet, path::PathBuf};
+use std::{
+    cell::Ref,
+    collections::{BTreeSet, HashMap, HashSet},
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+
+use super::{DesktopEntry, MimeType};
 
 #[derive(Debug, Default, Clone)]
 pub struct IconData {
     pub name: String,
     pub aliases: Vec<String>,
+    /// Human-language keywords and synonyms, see [crate::KeywordDatabase].
+    pub keywords: Vec<String>,
+    /// Installed applications that use the icon.
+    pub desktop_entries: Vec<DesktopEntry>,
+    /// MIME types that resolve to the icon.
+    pub mime_types: Vec<MimeType>,
+    /// Descriptive text of SVG icons, see [super::parse_svg_metadata].
+    pub svg_metadata: Vec<String>,
+    /// Names of other icons whose file has identical contents.
+    pub duplicates: Vec<String>,
     pub tags: Vec<String>,
+    /// Every size directory the theme has a file of the icon in, see
+    /// [scan_size_directories].
+    pub size_directories: BTreeSet<Option<SizeDirectory>>,
     pub symlink_target_index: Option<u32>,
     pub path: Option<PathBuf>,
     pub symlink_path: Option<PathBuf>,
+    /// Size of the icon file in bytes, following symlinks.
+    pub file_size: Option<u64>,
     pub is_symbolic: bool,
     pub is_symlink: bool,
     pub is_embedded: bool,
+    /// Whether the icon is one of the [crate::Favorites].
+    pub is_favorite: bool,
+    /// Hash of the rendered icon, see [super::perceptual_hash].
+    pub perceptual_hash: Option<u64>,
 }
 
 impl IconData {
@@ -23,6 +49,139 @@ impl IconData {
 
         self.aliases.extend(set);
     }
+
+    /// Alternative names the icon can be searched by: its aliases, keywords, the
+    /// metadata of the applications using it and the MIME types resolving to it.
+    pub fn search_terms(&self) -> impl Iterator<Item = &str> {
+        self.aliases
+            .iter()
+            .chain(&self.keywords)
+            .map(String::as_str)
+            .chain(self.desktop_entries.iter().flat_map(DesktopEntry::search_terms))
+            .chain(self.mime_types.iter().flat_map(MimeType::search_terms))
+    }
+
+    /// Returns the theme size directory the icon file is in, if any.
+    pub fn size_directory(&self) -> Option<SizeDirectory> {
+        self.path.as_deref().and_then(SizeDirectory::from_path)
+    }
+
+    /// Returns every size directory the theme has a file of the icon in.
+    ///
+    /// Until the theme was scanned, this is only the directory of the icon file.
+    pub fn all_size_directories(&self) -> Vec<Option<SizeDirectory>> {
+        if self.size_directories.is_empty() {
+            vec![self.size_directory()]
+        } else {
+            self.size_directories.iter().copied().collect()
+        }
+    }
+}
+
+/// Finds the theme directory an icon file is in, the one directly inside a directory of
+/// the icon theme search path.
+pub fn theme_directory(path: &Path, search_path: &[PathBuf]) -> Option<PathBuf> {
+    path.ancestors()
+        .find(|ancestor| {
+            ancestor
+                .parent()
+                .is_some_and(|parent| search_path.iter().any(|dir| dir == parent))
+        })
+        .map(Path::to_path_buf)
+}
+
+/// Lists the size directories of every icon file in a theme directory by icon name.
+///
+/// This reads the whole directory tree, so it should not be called on the main thread.
+pub fn scan_size_directories(theme_dir: &Path) -> HashMap<String, BTreeSet<Option<SizeDirectory>>> {
+    let mut directories: HashMap<_, BTreeSet<_>> = HashMap::new();
+    let mut pending = vec![theme_dir.to_path_buf()];
+
+    while let Some(current) = pending.pop() {
+        let Ok(read_dir) = std::fs::read_dir(&current) else {
+            continue;
+        };
+
+        for path in read_dir.flatten().map(|entry| entry.path()) {
+            if path.is_dir() {
+                pending.push(path);
+                continue;
+            }
+
+            let is_icon = path
+                .extension()
+                .is_some_and(|ext| ext == "svg" || ext == "png" || ext == "xpm");
+            let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+
+            if is_icon {
+                // Symbolic PNGs are named like `edit-copy-symbolic.symbolic.png`
+                let name = stem.strip_suffix(".symbolic").unwrap_or(stem);
+                directories
+                    .entry(name.to_string())
+                    .or_default()
+                    .insert(SizeDirectory::from_path(&path));
+            }
+        }
+    }
+
+    directories
+}
+
+/// The nominal size of an icon theme directory, e.g. `24x24`, `24x24@2` or `scalable`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum SizeDirectory {
+    Fixed { size: u32, scale: u32 },
+    Scalable,
+    Symbolic,
+}
+
+impl SizeDirectory {
+    /// Finds the size directory closest to the file in the given path.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        path.parent()?
+            .iter()
+            .rev()
+            .find_map(|component| component.to_str().and_then(Self::parse))
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "scalable" => return Some(SizeDirectory::Scalable),
+            "symbolic" => return Some(SizeDirectory::Symbolic),
+            _ => {}
+        }
+
+        let (dimensions, scale) = match name.split_once('@') {
+            Some((dimensions, scale)) => (dimensions, scale.trim_end_matches('x').parse().ok()?),
+            None => (name, 1),
+        };
+
+        let (width, height) = dimensions.split_once('x')?;
+        let size: u32 = width.parse().ok()?;
+
+        (height.parse() == Ok(size)).then_some(SizeDirectory::Fixed { size, scale })
+    }
+
+    /// The size in pixels an icon in this directory is expected to be.
+    pub fn pixel_size(&self) -> Option<u32> {
+        match self {
+            SizeDirectory::Fixed { size, scale } => Some(size * scale),
+            _ => None,
+        }
+    }
+}
+
+impl Display for SizeDirectory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SizeDirectory::Fixed { size, scale: 1 } => write!(f, "{size}x{size}"),
+            SizeDirectory::Fixed { size, scale } => write!(f, "{size}x{size}@{scale}"),
+            SizeDirectory::Scalable => f.write_str("scalable"),
+            SizeDirectory::Symbolic => f.write_str("symbolic"),
+        }
+    }
 }
 
 mod imp {
@@ -42,6 +201,8 @@ mod imp {
     pub struct IconObject {
         #[property(name = "name", get, set = set_name, member = name, type = String)]
         #[property(name = "aliases", get, set, member = aliases, type = Vec<String>)]
+        #[property(name = "keywords", get, set, member = keywords, type = Vec<String>)]
+        #[property(name = "duplicates", get, set, member = duplicates, type = Vec<String>)]
         #[property(name = "tags", get, member = tags, type = Vec<String>)]
         #[property(
             name = "symlink-target-index",
@@ -53,6 +214,7 @@ mod imp {
         #[property(name = "is-symbolic", get, member = is_symbolic, type = bool)]
         #[property(name = "is-symlink", get, member = is_symlink, type = bool)]
         #[property(name = "is-embedded", get, member = is_embedded, type = bool)]
+        #[property(name = "is-favorite", get, set, member = is_favorite, type = bool)]
         #[property(
             name = "path",
             get = |o: &Self| o.data.borrow().path.as_ref().map(|p| p.display().to_string()),
@@ -121,6 +283,8 @@ mod imp {
 
             for (name, changed) in [
                 ("aliases", current_data.aliases != data.aliases),
+                ("keywords", current_data.keywords != data.keywords),
+                ("duplicates", current_data.duplicates != data.duplicates),
                 ("tags", current_data.tags != data.tags),
                 ("path", current_data.path != data.path),
                 ("is-symbolic", current_data.is_symbolic != data.is_symbolic),
@@ -171,6 +335,7 @@ mod imp {
                         data.symlink_path = None;
                     }
 
+                    data.file_size = std::fs::metadata(&path).map(|m| m.len()).ok();
                     data.path = Some(path);
                     data.is_symlink = is_symlink;
                     data.tags = get_tags(&data);
@@ -275,6 +440,63 @@ mod imp {
             assert_eq!(get_tags(&icon), vec!["Adwaita"]);
         }
 
+        #[test]
+        fn test_size_directory_from_path() {
+            for (path, expected) in [
+                (
+                    "/usr/share/icons/hicolor/24x24/apps/test.png",
+                    Some(SizeDirectory::Fixed { size: 24, scale: 1 }),
+                ),
+                (
+                    "/usr/share/icons/Adwaita/16x16@2/actions/test.png",
+                    Some(SizeDirectory::Fixed { size: 16, scale: 2 }),
+                ),
+                (
+                    "/usr/share/icons/Papirus/apps/scalable/test.svg",
+                    Some(SizeDirectory::Scalable),
+                ),
+                ("/usr/share/icons/Adwaita/test.svg", None),
+            ] {
+                assert_eq!(SizeDirectory::from_path(Path::new(path)), expected);
+            }
+        }
+
+        #[test]
+        fn test_all_size_directories() {
+            let mut icon = IconData {
+                path: Some(PathBuf::from(
+                    "/usr/share/icons/hicolor/64x64/apps/test.png",
+                )),
+                ..Default::default()
+            };
+            assert_eq!(
+                icon.all_size_directories(),
+                vec![Some(SizeDirectory::Fixed { size: 64, scale: 1 })]
+            );
+
+            icon.size_directories = BTreeSet::from([
+                Some(SizeDirectory::Fixed { size: 16, scale: 1 }),
+                Some(SizeDirectory::Scalable),
+            ]);
+            assert_eq!(
+                icon.all_size_directories(),
+                vec![
+                    Some(SizeDirectory::Fixed { size: 16, scale: 1 }),
+                    Some(SizeDirectory::Scalable)
+                ]
+            );
+
+            let search_path = [PathBuf::from("/usr/share/icons")];
+            assert_eq!(
+                theme_directory(icon.path.as_deref().unwrap(), &search_path),
+                Some(PathBuf::from("/usr/share/icons/hicolor"))
+            );
+            assert_eq!(
+                theme_directory(Path::new("/tmp/test.png"), &search_path),
+                None
+            );
+        }
+
         #[test]
         fn test_get_tags_from_path() {
             for (path, expected) in [
@@ -320,6 +542,34 @@ impl IconObject {
         self.imp().data.borrow().symlink_target_index.is_some()
     }
 
+    pub fn has_duplicates(&self) -> bool {
+        !self.imp().data.borrow().duplicates.is_empty()
+    }
+
+    pub fn perceptual_hash(&self) -> Option<u64> {
+        self.imp().data.borrow().perceptual_hash
+    }
+
+    pub fn set_perceptual_hash(&self, hash: u64) {
+        self.imp().data.borrow_mut().perceptual_hash = Some(hash);
+    }
+
+    pub fn set_desktop_entries(&self, entries: Vec<DesktopEntry>) {
+        self.imp().data.borrow_mut().desktop_entries = entries;
+    }
+
+    pub fn set_mime_types(&self, mime_types: Vec<MimeType>) {
+        self.imp().data.borrow_mut().mime_types = mime_types;
+    }
+
+    pub fn set_svg_metadata(&self, metadata: Vec<String>) {
+        self.imp().data.borrow_mut().svg_metadata = metadata;
+    }
+
+    pub fn set_size_directories(&self, directories: BTreeSet<Option<SizeDirectory>>) {
+        self.imp().data.borrow_mut().size_directories = directories;
+    }
+
     pub fn data(&self) -> Ref<IconData> {
         self.imp().data.borrow()
     }
@@ -330,3 +580,24 @@ impl From<IconObject> for IconData {
         icon.data().clone()
     }
 }
+
+#[cfg(test)]
+impl IconData {
+    /// An icon at the given path in `/usr/share/icons`, tagged with the directories of the
+    /// path below it, like icons of a loaded theme.
+    pub(crate) fn at_path(name: &str, path: &str) -> Self {
+        let path = PathBuf::from(path);
+        IconData {
+            name: name.to_string(),
+            tags: path
+                .parent()
+                .unwrap()
+                .iter()
+                .skip(4)
+                .map(|s| s.to_str().unwrap().to_string())
+                .collect(),
+            path: Some(path),
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,232 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use gtk::glib;
+
+const DESKTOP_ENTRY_GROUP: &str = "[Desktop Entry]";
+
+/// The metadata of an installed application that uses an icon.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DesktopEntry {
+    /// The desktop file ID, e.g. `org.gnome.Nautilus.desktop`.
+    pub id: String,
+    pub name: Option<String>,
+    pub generic_name: Option<String>,
+    pub keywords: Vec<String>,
+    /// The icon name or path from the `Icon` key.
+    pub icon: String,
+}
+
+impl DesktopEntry {
+    /// Parses the unlocalized keys of the `[Desktop Entry]` group of a desktop file.
+    ///
+    /// Returns [None] for hidden entries and entries without an icon.
+    pub fn parse(id: &str, source: &str) -> Option<Self> {
+        let mut entry = DesktopEntry {
+            id: id.to_string(),
+            ..Default::default()
+        };
+        let mut in_group = false;
+
+        for line in source.lines().map(str::trim) {
+            if line.starts_with('[') {
+                in_group = line == DESKTOP_ENTRY_GROUP;
+                continue;
+            }
+
+            if !in_group || line.starts_with('#') {
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+
+            match key.trim_end() {
+                "Name" => entry.name = Some(unescape(value.trim_start())),
+                "GenericName" => entry.generic_name = Some(unescape(value.trim_start())),
+                "Keywords" => entry.keywords = split_list(value.trim_start()),
+                "Icon" => entry.icon = unescape(value.trim_start()),
+                "Hidden" if value.trim() == "true" => return None,
+                _ => {}
+            }
+        }
+
+        (!entry.icon.is_empty()).then_some(entry)
+    }
+
+    /// The text the entry can be searched by.
+    pub fn search_terms(&self) -> impl Iterator<Item = &str> {
+        [
+            Some(self.id.as_str()),
+            self.name.as_deref(),
+            self.generic_name.as_deref(),
+        ]
+        .into_iter()
+        .flatten()
+        .chain(self.keywords.iter().map(String::as_str))
+    }
+}
+
+/// The XDG `applications` directories, in order of precedence.
+pub fn application_dirs() -> Vec<PathBuf> {
+    std::iter::once(glib::user_data_dir())
+        .chain(glib::system_data_dirs())
+        .map(|dir| dir.join("applications"))
+        .collect()
+}
+
+/// Reads the desktop files of all installed applications, grouped by their icon name.
+///
+/// Desktop files with the same ID as one in a directory of higher precedence are skipped,
+/// as are entries that reference their icon by path.
+pub fn load_desktop_entries() -> HashMap<String, Vec<DesktopEntry>> {
+    let mut seen = HashSet::new();
+    let mut entries: HashMap<String, Vec<DesktopEntry>> = HashMap::new();
+
+    for dir in application_dirs() {
+        for (id, path) in desktop_files(&dir) {
+            if !seen.insert(id.clone()) {
+                continue;
+            }
+
+            let source = match std::fs::read_to_string(&path) {
+                Ok(source) => source,
+                Err(err) => {
+                    log::debug!("Failed to read \"{}\": {}", path.display(), err);
+                    continue;
+                }
+            };
+
+            if let Some(entry) = DesktopEntry::parse(&id, &source)
+                && !entry.icon.contains('/')
+            {
+                entries.entry(entry.icon.clone()).or_default().push(entry);
+            }
+        }
+    }
+
+    entries
+}
+
+/// Lists the desktop files in an applications directory with their desktop file ID.
+///
+/// The ID is the path relative to the directory, with slashes replaced by dashes.
+fn desktop_files(dir: &Path) -> Vec<(String, PathBuf)> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+
+    while let Some(current) = pending.pop() {
+        let Ok(read_dir) = std::fs::read_dir(&current) else {
+            continue;
+        };
+
+        for path in read_dir.flatten().map(|entry| entry.path()) {
+            if path.is_dir() {
+                pending.push(path);
+            } else if path.extension().is_some_and(|ext| ext == "desktop") {
+                let Ok(relative) = path.strip_prefix(dir) else {
+                    continue;
+                };
+
+                let id = relative.to_string_lossy().replace('/', "-");
+                files.push((id, path));
+            }
+        }
+    }
+
+    files
+}
+
+/// Resolves the escape sequences of a desktop file string value.
+fn unescape(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('s') => result.push(' '),
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some('r') => result.push('\r'),
+            Some(other) => result.push(other),
+            None => result.push('\\'),
+        }
+    }
+
+    result
+}
+
+/// Splits a semicolon separated list value, respecting escaped semicolons.
+fn split_list(value: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut current = String::new();
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                current.push(c);
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+            }
+            ';' => items.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    items.push(current);
+
+    items
+        .iter()
+        .map(|item| unescape(item.trim()))
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let source = "\
+[Desktop Entry]
+Name=Files
+Name[de]=Dateien
+GenericName = File\\sManager
+Keywords=folder;manager;explore;disk\\;filesystem;
+Icon=org.gnome.Nautilus
+
+[Desktop Action new-window]
+Name=New Window
+Icon=window-new
+";
+
+        let entry = DesktopEntry::parse("org.gnome.Nautilus.desktop", source).unwrap();
+        assert_eq!(entry.name.as_deref(), Some("Files"));
+        assert_eq!(entry.generic_name.as_deref(), Some("File Manager"));
+        assert_eq!(
+            entry.keywords,
+            vec!["folder", "manager", "explore", "disk;filesystem"]
+        );
+        assert_eq!(entry.icon, "org.gnome.Nautilus");
+        assert_eq!(
+            entry.search_terms().collect::<Vec<_>>()[..3],
+            ["org.gnome.Nautilus.desktop", "Files", "File Manager"]
+        );
+
+        assert!(DesktopEntry::parse("hidden.desktop", &format!("{source}Hidden=true")).is_some());
+        assert!(
+            DesktopEntry::parse("hidden.desktop", "[Desktop Entry]\nIcon=a\nHidden=true").is_none()
+        );
+        assert!(DesktopEntry::parse("no-icon.desktop", "[Desktop Entry]\nName=A").is_none());
+    }
+}
@@ -0,0 +1,59 @@
+use gtk::{gdk, gio, prelude::*};
+
+use super::{IconObject, PngExport};
+
+/// The formats an icon is offered in when it's dragged: its name as text, the file it's
+/// loaded from and a rendering at the given size in the colors of the widget.
+pub fn icon_content(icon: &IconObject, size: u32, widget: &gtk::Widget) -> gdk::ContentProvider {
+    let name = icon.name();
+    let mut providers = vec![gdk::ContentProvider::for_value(&name.to_value())];
+
+    if let Some(path) = icon.data().path.clone() {
+        let files = gdk::FileList::from_array(&[gio::File::for_path(path)]);
+        providers.push(gdk::ContentProvider::for_value(&files.to_value()));
+    }
+
+    let mut export = PngExport {
+        size,
+        scale: widget.scale_factor().max(1) as u32,
+        ..Default::default()
+    };
+    export.palette[0] = widget.color();
+
+    if let Some(renderer) = widget.native().and_then(|native| native.renderer()) {
+        match export.render_texture(&name, &renderer) {
+            Ok(texture) => providers.push(gdk::ContentProvider::for_value(&texture.to_value())),
+            Err(err) => log::warn!("Failed to render \"{}\" for dragging: {}", name, err),
+        }
+    }
+
+    gdk::ContentProvider::new_union(&providers)
+}
+
+/// Lets the icon be dragged out of the widget, see [icon_content].
+///
+/// The closure returns the icon and the size it's shown at when a drag starts, so widgets
+/// that are rebound to other icons keep a single drag source. The widget owns the drag
+/// source, so the closure should only hold a weak reference to it.
+pub fn add_icon_drag_source<F>(widget: &impl IsA<gtk::Widget>, icon: F)
+where
+    F: Fn() -> Option<(IconObject, u32)> + 'static,
+{
+    let drag_source = gtk::DragSource::builder()
+        .actions(gdk::DragAction::COPY)
+        .build();
+
+    drag_source.connect_prepare(move |source, _, _| {
+        let (icon, size) = icon()?;
+        let widget = source.widget()?;
+
+        if let Some(paintable) = icon.paintable() {
+            let hot_spot = paintable.intrinsic_width() / 2;
+            source.set_icon(Some(&paintable), hot_spot, hot_spot);
+        }
+
+        Some(icon_content(&icon, size, &widget))
+    });
+
+    widget.add_controller(drag_source);
+}
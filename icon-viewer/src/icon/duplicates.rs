@@ -0,0 +1,92 @@
+use std::{
+    collections::HashMap,
+    hash::{DefaultHasher, Hash, Hasher},
+    path::Path,
+};
+
+/// The length and hash of the contents of a file.
+///
+/// Files with the same key are only candidates for duplicates, as hashes can collide, see
+/// [group_duplicates]. The hash is only meant for comparing files within a single run of the
+/// application.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ContentKey {
+    pub length: u64,
+    pub hash: u64,
+}
+
+/// Hashes the contents of the file at the given path.
+pub fn content_key(path: &Path) -> std::io::Result<ContentKey> {
+    let bytes = std::fs::read(path)?;
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+
+    Ok(ContentKey {
+        length: bytes.len() as u64,
+        hash: hasher.finish(),
+    })
+}
+
+/// Whether the files have the same contents, comparing them byte by byte.
+pub fn same_content(a: &Path, b: &Path) -> std::io::Result<bool> {
+    Ok(std::fs::read(a)? == std::fs::read(b)?)
+}
+
+/// Groups the given indices by their contents, only keeping groups with more than one entry.
+///
+/// Indices with the same key are compared with `same_content` before they're grouped, so a
+/// hash collision doesn't make different files duplicates.
+pub fn group_duplicates(
+    keys: &[(usize, ContentKey)],
+    mut same_content: impl FnMut(usize, usize) -> bool,
+) -> Vec<Vec<usize>> {
+    let mut candidates: HashMap<ContentKey, Vec<usize>> = HashMap::new();
+
+    for (index, key) in keys {
+        candidates.entry(*key).or_default().push(*index);
+    }
+
+    let mut groups = Vec::new();
+
+    for candidates in candidates.into_values().filter(|group| group.len() > 1) {
+        let mut same: Vec<Vec<usize>> = Vec::new();
+
+        for index in candidates {
+            match same.iter_mut().find(|group| same_content(group[0], index)) {
+                Some(group) => group.push(index),
+                None => same.push(vec![index]),
+            }
+        }
+
+        groups.extend(same.into_iter().filter(|group| group.len() > 1));
+    }
+
+    groups.sort();
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_duplicates() {
+        let contents = ["a", "bb", "a", "cc", "bb", "a", "dd"];
+        let key = |hash| ContentKey { length: 1, hash };
+        // "dd" collides with "bb"
+        let keys = [
+            (0, key(10)),
+            (1, key(20)),
+            (2, key(10)),
+            (3, key(30)),
+            (4, key(20)),
+            (5, key(10)),
+            (6, key(20)),
+        ];
+
+        assert_eq!(
+            group_duplicates(&keys, |a, b| contents[a] == contents[b]),
+            vec![vec![0, 2, 5], vec![1, 4]]
+        );
+    }
+}
@@ -0,0 +1,294 @@
+use std::path::{Component, Path, PathBuf};
+
+use color_eyre::{Result, eyre::eyre};
+use gtk::{gdk, glib, graphene, gsk, prelude::*};
+
+use crate::icon_theme;
+
+/// The colors symbolic icons are recolored with when no palette is given:
+/// foreground, error, warning and success.
+pub const DEFAULT_SYMBOLIC_PALETTE: [&str; 4] = ["#000000", "#e01b24", "#e5a50a", "#2ec27e"];
+
+/// The file name template batch exports use unless another is given.
+pub const DEFAULT_FILE_NAME_TEMPLATE: &str = "{name}-{size}";
+
+/// How an icon is rendered to a PNG.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PngExport {
+    /// The size of the icon in logical pixels.
+    pub size: u32,
+    /// The scale factor, e.g. 2 for a HiDPI rendering.
+    pub scale: u32,
+    /// The space around the icon in logical pixels.
+    pub padding: u32,
+    /// The background color, transparent if [None].
+    pub background: Option<gdk::RGBA>,
+    /// The colors symbolic icons are recolored with, in the order GTK expects.
+    pub palette: Vec<gdk::RGBA>,
+}
+
+impl Default for PngExport {
+    fn default() -> Self {
+        PngExport {
+            size: 128,
+            scale: 1,
+            padding: 0,
+            background: None,
+            palette: DEFAULT_SYMBOLIC_PALETTE
+                .iter()
+                .map(|color| gdk::RGBA::parse(*color).expect("Invalid default palette"))
+                .collect(),
+        }
+    }
+}
+
+impl PngExport {
+    /// The width and height of the exported image in device pixels.
+    pub fn pixel_size(&self) -> u32 {
+        (self.size + 2 * self.padding) * self.scale
+    }
+
+    /// The file name the export is suggested to be saved as.
+    pub fn file_name(&self, icon_name: &str) -> String {
+        match self.scale {
+            1 => format!("{}-{}.png", icon_name, self.size),
+            scale => format!("{}-{}@{}x.png", icon_name, self.size, scale),
+        }
+    }
+
+    /// Renders the icon from the current theme and encodes it as PNG.
+    pub fn render(&self, icon_name: &str, renderer: &gsk::Renderer) -> Result<glib::Bytes> {
+        Ok(self
+            .render_texture(icon_name, renderer)?
+            .save_to_png_bytes())
+    }
+
+    /// Renders the icon from the current theme.
+    ///
+    /// The icon is looked up at the export's size and scale, so themes with bitmaps
+    /// for higher scales are used instead of scaling up the smaller bitmaps.
+    pub fn render_texture(
+        &self,
+        icon_name: &str,
+        renderer: &gsk::Renderer,
+    ) -> Result<gdk::Texture> {
+        if self.size == 0 || self.scale == 0 {
+            return Err(eyre!("Cannot export \"{}\" at size zero", icon_name));
+        }
+
+        let paintable = icon_theme().lookup_icon(
+            icon_name,
+            &[],
+            self.size as i32,
+            self.scale as i32,
+            gtk::TextDirection::Ltr,
+            gtk::IconLookupFlags::empty(),
+        );
+
+        let pixel_size = self.pixel_size() as f32;
+        let bounds = graphene::Rect::new(0.0, 0.0, pixel_size, pixel_size);
+        let snapshot = gtk::Snapshot::new();
+
+        // Keeps the viewport the size of the image, even if the icon is transparent
+        snapshot.append_color(&self.background.unwrap_or(gdk::RGBA::TRANSPARENT), &bounds);
+        snapshot.scale(self.scale as f32, self.scale as f32);
+        snapshot.translate(&graphene::Point::new(
+            self.padding as f32,
+            self.padding as f32,
+        ));
+        paintable.snapshot_symbolic(&snapshot, self.size as f64, self.size as f64, &self.palette);
+
+        let node = snapshot
+            .to_node()
+            .ok_or_else(|| eyre!("Failed to render \"{}\"", icon_name))?;
+
+        Ok(renderer.render_texture(node, Some(&bounds)))
+    }
+}
+
+/// Renders many icons at one or more sizes into a directory.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchExport {
+    pub directory: PathBuf,
+    pub sizes: Vec<u32>,
+    /// The path of each PNG relative to the directory, without the extension.
+    ///
+    /// `{name}`, `{size}` and `{theme}` are replaced by the icon name, the size and the theme
+    /// name, so `{size}x{size}/{name}` sorts the icons into a folder per size.
+    pub template: String,
+    pub theme: String,
+    /// How the icons are rendered, the size is replaced by each of the sizes.
+    pub png: PngExport,
+}
+
+impl BatchExport {
+    /// Parses sizes separated by commas or spaces, e.g. `16, 32 64`, without duplicates.
+    pub fn parse_sizes(text: &str) -> Result<Vec<u32>> {
+        let mut sizes = Vec::new();
+
+        for part in text
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|part| !part.is_empty())
+        {
+            let size = part
+                .parse::<u32>()
+                .ok()
+                .filter(|size| *size > 0)
+                .ok_or_else(|| eyre!("Invalid size: \"{}\"", part))?;
+
+            if !sizes.contains(&size) {
+                sizes.push(size);
+            }
+        }
+
+        if sizes.is_empty() {
+            return Err(eyre!("No sizes given"));
+        }
+
+        Ok(sizes)
+    }
+
+    /// Checks that every icon and size is written to its own file inside the directory.
+    pub fn validate(&self, icon_count: usize) -> Result<()> {
+        if self.sizes.is_empty() {
+            return Err(eyre!("No sizes given"));
+        }
+
+        validate_template(&self.template)?;
+
+        if icon_count > 1 && !self.template.contains("{name}") {
+            return Err(eyre!(
+                "The file name needs {{name}} to tell the icons apart"
+            ));
+        }
+
+        if self.sizes.len() > 1 && !self.template.contains("{size}") {
+            return Err(eyre!(
+                "The file name needs {{size}} to tell the sizes apart"
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// The path the icon is exported to at the given size.
+    pub fn path(&self, icon_name: &str, size: u32) -> PathBuf {
+        let file_name = expand_template(&self.template, icon_name, size, &self.theme);
+
+        self.directory.join(file_name + ".png")
+    }
+
+    /// Every combination of the icon names and sizes, in the order they're exported.
+    pub fn jobs<'a>(&'a self, names: &'a [String]) -> impl Iterator<Item = (&'a str, u32)> {
+        names
+            .iter()
+            .flat_map(move |name| self.sizes.iter().map(move |size| (name.as_str(), *size)))
+    }
+
+    pub fn render(
+        &self,
+        icon_name: &str,
+        size: u32,
+        renderer: &gsk::Renderer,
+    ) -> Result<glib::Bytes> {
+        PngExport {
+            size,
+            ..self.png.clone()
+        }
+        .render(icon_name, renderer)
+    }
+}
+
+/// Checks that a file name template stays inside the folder it's exported to.
+pub fn validate_template(template: &str) -> Result<()> {
+    if template.trim().is_empty()
+        || Path::new(template)
+            .components()
+            .any(|component| !matches!(component, Component::Normal(_)))
+    {
+        return Err(eyre!(
+            "The file name must be a relative path inside the folder"
+        ));
+    }
+
+    Ok(())
+}
+
+/// Replaces `{name}`, `{size}` and `{theme}` in a file name template.
+pub fn expand_template(template: &str, icon_name: &str, size: u32, theme: &str) -> String {
+    template
+        .replace("{name}", icon_name)
+        .replace("{size}", &size.to_string())
+        .replace("{theme}", theme)
+}
+
+/// Writes a rendered PNG, creating the folders of its path.
+pub fn write_png(path: &Path, bytes: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    std::fs::write(path, bytes)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pixel_size() {
+        let mut export = PngExport {
+            size: 64,
+            ..Default::default()
+        };
+        assert_eq!(export.pixel_size(), 64);
+        assert_eq!(export.file_name("user-trash"), "user-trash-64.png");
+
+        export.scale = 2;
+        export.padding = 8;
+        assert_eq!(export.pixel_size(), 160);
+        assert_eq!(export.file_name("user-trash"), "user-trash-64@2x.png");
+        assert_eq!(export.palette.len(), 4);
+    }
+
+    #[test]
+    fn test_batch_export() {
+        assert_eq!(
+            BatchExport::parse_sizes("16, 32 32,,64").unwrap(),
+            vec![16, 32, 64]
+        );
+        assert!(BatchExport::parse_sizes("16, big").is_err());
+        assert!(BatchExport::parse_sizes("0").is_err());
+        assert!(BatchExport::parse_sizes(" ").is_err());
+
+        let mut export = BatchExport {
+            directory: PathBuf::from("/tmp/icons"),
+            sizes: vec![16, 32],
+            template: String::from("{theme}/{size}x{size}/{name}"),
+            theme: String::from("Adwaita"),
+            png: PngExport::default(),
+        };
+        assert!(export.validate(2).is_ok());
+        assert_eq!(
+            export.path("user-trash", 16),
+            Path::new("/tmp/icons/Adwaita/16x16/user-trash.png")
+        );
+
+        let names = [String::from("a"), String::from("b")];
+        assert_eq!(
+            export.jobs(&names).collect::<Vec<_>>(),
+            vec![("a", 16), ("a", 32), ("b", 16), ("b", 32)]
+        );
+
+        export.template = String::from("{name}");
+        assert!(export.validate(2).is_err());
+        export.sizes = vec![16];
+        assert!(export.validate(2).is_ok());
+
+        export.template = String::from("../{name}");
+        assert!(export.validate(2).is_err());
+        export.template = String::from("/{name}");
+        assert!(export.validate(2).is_err());
+    }
+}
@@ -0,0 +1,244 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+};
+
+use color_eyre::Result;
+use gtk::glib;
+
+/// A MIME type from the shared-mime-info database.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MimeType {
+    /// The MIME type, e.g. `application/pdf`.
+    pub name: String,
+    /// The unlocalized description, e.g. `PDF document`.
+    pub comment: Option<String>,
+    pub icon: Option<String>,
+    pub generic_icon: Option<String>,
+    /// The file name patterns, e.g. `*.pdf`.
+    pub globs: Vec<String>,
+}
+
+impl MimeType {
+    /// Parses the `mime-type` elements of a shared-mime-info package file.
+    pub fn parse_package(source: &str) -> Result<Vec<Self>> {
+        // The packages installed by shared-mime-info start with their DTD
+        let options = roxmltree::ParsingOptions {
+            allow_dtd: true,
+            ..Default::default()
+        };
+        let document = roxmltree::Document::parse_with_options(source, options)?;
+
+        let mime_types = document
+            .root_element()
+            .children()
+            .filter(|node| node.has_tag_name("mime-type"))
+            .filter_map(|node| {
+                let child_attribute = |tag: &str| {
+                    node.children()
+                        .find(|child| child.has_tag_name(tag))
+                        .and_then(|child| child.attribute("name"))
+                        .map(str::to_string)
+                };
+
+                let comment = node
+                    .children()
+                    .find(|child| {
+                        child.has_tag_name("comment")
+                            && !child
+                                .attributes()
+                                .any(|attribute| attribute.name() == "lang")
+                    })
+                    .and_then(|child| child.text())
+                    .map(str::to_string);
+
+                Some(MimeType {
+                    name: node.attribute("type")?.to_string(),
+                    comment,
+                    icon: child_attribute("icon"),
+                    generic_icon: child_attribute("generic-icon"),
+                    globs: node
+                        .children()
+                        .filter(|child| child.has_tag_name("glob"))
+                        .filter_map(|child| child.attribute("pattern"))
+                        .map(str::to_string)
+                        .collect(),
+                })
+            })
+            .collect();
+
+        Ok(mime_types)
+    }
+
+    /// Fills in the fields missing from this definition with the ones of another package.
+    ///
+    /// The fields that are already set take precedence, the globs of both are kept.
+    pub fn merge(&mut self, other: MimeType) {
+        self.comment = self.comment.take().or(other.comment);
+        self.icon = self.icon.take().or(other.icon);
+        self.generic_icon = self.generic_icon.take().or(other.generic_icon);
+
+        for glob in other.globs {
+            if !self.globs.contains(&glob) {
+                self.globs.push(glob);
+            }
+        }
+    }
+
+    /// The icon names the MIME type can be displayed with, in order of preference.
+    ///
+    /// Follows the shared-mime-info specification: the explicit icon, the MIME type with
+    /// the slash replaced by a dash, the generic icon and finally `<media>-x-generic`.
+    pub fn icon_candidates(&self) -> Vec<String> {
+        let media = self.name.split('/').next().unwrap_or_default();
+
+        self.icon
+            .iter()
+            .cloned()
+            .chain([self.name.replace('/', "-")])
+            .chain(self.generic_icon.iter().cloned())
+            .chain([format!("{media}-x-generic")])
+            .collect()
+    }
+
+    /// The text the MIME type can be searched by.
+    pub fn search_terms(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.name.as_str())
+            .chain(self.comment.as_deref())
+            .chain(self.globs.iter().map(String::as_str))
+    }
+}
+
+/// The `mime/packages` directories, in order of precedence.
+pub fn mime_package_dirs() -> Vec<PathBuf> {
+    std::iter::once(glib::user_data_dir())
+        .chain(glib::system_data_dirs())
+        .map(|dir| dir.join("mime").join("packages"))
+        .collect()
+}
+
+/// Maps every MIME type to the first of its icon candidates that is in the given icon names.
+///
+/// Returns the MIME types grouped by the icon they resolve to. MIME types defined in
+/// multiple package files are merged, preferring the fields of higher precedence.
+pub fn load_mime_types(icon_names: &HashSet<String>) -> HashMap<String, Vec<MimeType>> {
+    let mut definitions: Vec<MimeType> = Vec::new();
+    let mut indices: HashMap<String, usize> = HashMap::new();
+
+    for dir in mime_package_dirs() {
+        let Ok(read_dir) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+
+        let mut packages: Vec<_> = read_dir
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "xml"))
+            .collect();
+        packages.sort();
+
+        for path in packages {
+            let parsed = std::fs::read_to_string(&path)
+                .map_err(Into::into)
+                .and_then(|source| MimeType::parse_package(&source));
+
+            let package = match parsed {
+                Ok(package) => package,
+                Err(err) => {
+                    log::warn!("Failed to load \"{}\": {}", path.display(), err);
+                    continue;
+                }
+            };
+
+            for mime_type in package {
+                match indices.get(&mime_type.name) {
+                    Some(&index) => definitions[index].merge(mime_type),
+                    None => {
+                        indices.insert(mime_type.name.clone(), definitions.len());
+                        definitions.push(mime_type);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut mime_types: HashMap<String, Vec<MimeType>> = HashMap::new();
+
+    for mime_type in definitions {
+        let icon = mime_type
+            .icon_candidates()
+            .into_iter()
+            .find(|candidate| icon_names.contains(candidate));
+
+        if let Some(icon) = icon {
+            mime_types.entry(icon).or_default().push(mime_type);
+        }
+    }
+
+    mime_types
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_package() {
+        let source = r#"<?xml version="1.0" encoding="UTF-8"?>
+<mime-info xmlns="http://www.freedesktop.org/standards/shared-mime-info">
+  <mime-type type="application/pdf">
+    <comment>PDF document</comment>
+    <comment xml:lang="de">PDF-Dokument</comment>
+    <generic-icon name="x-office-document"/>
+    <glob pattern="*.pdf"/>
+  </mime-type>
+  <mime-type type="text/x-readme">
+    <comment>README document</comment>
+    <icon name="text-x-readme"/>
+  </mime-type>
+</mime-info>"#;
+
+        let mime_types = MimeType::parse_package(source).unwrap();
+        assert_eq!(mime_types.len(), 2);
+
+        let pdf = &mime_types[0];
+        assert_eq!(pdf.comment.as_deref(), Some("PDF document"));
+        assert_eq!(
+            pdf.icon_candidates(),
+            vec![
+                "application-pdf",
+                "x-office-document",
+                "application-x-generic"
+            ]
+        );
+        assert_eq!(
+            pdf.search_terms().collect::<Vec<_>>(),
+            vec!["application/pdf", "PDF document", "*.pdf"]
+        );
+
+        assert_eq!(mime_types[1].icon_candidates()[0], "text-x-readme");
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut user = MimeType {
+            name: String::from("text/x-readme"),
+            icon: Some(String::from("text-x-readme")),
+            globs: vec![String::from("README")],
+            ..Default::default()
+        };
+
+        user.merge(MimeType {
+            name: String::from("text/x-readme"),
+            comment: Some(String::from("README document")),
+            icon: Some(String::from("text-x-generic")),
+            generic_icon: Some(String::from("text-x-generic")),
+            globs: vec![String::from("README*"), String::from("README")],
+        });
+
+        assert_eq!(user.comment.as_deref(), Some("README document"));
+        assert_eq!(user.icon.as_deref(), Some("text-x-readme"));
+        assert_eq!(user.generic_icon.as_deref(), Some("text-x-generic"));
+        assert_eq!(user.globs, vec!["README", "README*"]);
+    }
+}
@@ -1,5 +1,23 @@
 mod data;
 mod cell;
+mod desktop;
+mod drag;
+mod duplicates;
+mod export;
+mod mime;
+mod raster;
+mod similarity;
+mod sprite;
+mod svg;
 
 pub use data::*;
 pub use cell::*;
+pub use desktop::*;
+pub use drag::*;
+pub use duplicates::*;
+pub use export::*;
+pub use mime::*;
+pub use raster::*;
+pub use similarity::*;
+pub use sprite::*;
+pub use svg::*;
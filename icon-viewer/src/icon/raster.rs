@@ -0,0 +1,219 @@
+use std::{fmt::Display, path::Path};
+
+use color_eyre::{Result, eyre::eyre};
+
+use super::SizeDirectory;
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RasterFormat {
+    Png,
+    Xpm,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorType {
+    Grayscale,
+    Rgb,
+    Indexed,
+    GrayscaleAlpha,
+    Rgba,
+}
+
+impl Display for ColorType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ColorType::Grayscale => "Grayscale",
+            ColorType::Rgb => "RGB",
+            ColorType::Indexed => "Indexed",
+            ColorType::GrayscaleAlpha => "Grayscale + Alpha",
+            ColorType::Rgba => "RGBA",
+        })
+    }
+}
+
+/// Pixel information read from the header of a raster icon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RasterInfo {
+    pub format: RasterFormat,
+    pub width: u32,
+    pub height: u32,
+    /// Bits per sample, XPM files don't have one.
+    pub bit_depth: Option<u8>,
+    pub color_type: ColorType,
+    pub has_alpha: bool,
+}
+
+impl RasterInfo {
+    /// Reads the raster info of the file at the given path, based on its extension.
+    pub fn read(path: &Path) -> Result<Self> {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("png") => Self::parse_png(&std::fs::read(path)?),
+            Some("xpm") => Self::parse_xpm(&std::fs::read_to_string(path)?),
+            _ => Err(eyre!("Unsupported raster format: \"{}\"", path.display())),
+        }
+    }
+
+    pub fn parse_png(bytes: &[u8]) -> Result<Self> {
+        if !bytes.starts_with(PNG_SIGNATURE) {
+            return Err(eyre!("Missing PNG signature"));
+        }
+
+        let mut info = None;
+        let mut has_transparency = false;
+        let mut offset = PNG_SIGNATURE.len();
+
+        while let Some(header) = bytes.get(offset..offset + 8) {
+            let length = u32::from_be_bytes(header[..4].try_into()?) as usize;
+            let kind = &header[4..8];
+            let data = bytes
+                .get(offset + 8..offset + 8 + length)
+                .ok_or_else(|| eyre!("Truncated PNG chunk"))?;
+
+            match kind {
+                b"IHDR" if data.len() >= 10 => {
+                    let color_type = match data[9] {
+                        0 => ColorType::Grayscale,
+                        2 => ColorType::Rgb,
+                        3 => ColorType::Indexed,
+                        4 => ColorType::GrayscaleAlpha,
+                        6 => ColorType::Rgba,
+                        other => return Err(eyre!("Invalid PNG color type: {}", other)),
+                    };
+
+                    info = Some(RasterInfo {
+                        format: RasterFormat::Png,
+                        width: u32::from_be_bytes(data[0..4].try_into()?),
+                        height: u32::from_be_bytes(data[4..8].try_into()?),
+                        bit_depth: Some(data[8]),
+                        color_type,
+                        has_alpha: false,
+                    });
+                }
+                b"tRNS" => has_transparency = true,
+                b"IDAT" | b"IEND" => break,
+                _ => {}
+            }
+
+            // Chunk header, data and CRC
+            offset += 8 + length + 4;
+        }
+
+        let mut info = info.ok_or_else(|| eyre!("Missing PNG header"))?;
+        info.has_alpha = has_transparency
+            || matches!(info.color_type, ColorType::GrayscaleAlpha | ColorType::Rgba);
+
+        Ok(info)
+    }
+
+    pub fn parse_xpm(source: &str) -> Result<Self> {
+        let mut strings = source.split('"').skip(1).step_by(2);
+
+        let values: Vec<u32> = strings
+            .next()
+            .ok_or_else(|| eyre!("Missing XPM values"))?
+            .split_whitespace()
+            .map(str::parse)
+            .collect::<Result<_, _>>()?;
+
+        let [width, height, colors, chars_per_pixel, ..] = values[..] else {
+            return Err(eyre!("Invalid XPM values"));
+        };
+
+        let has_alpha = strings.take(colors as usize).any(|color| {
+            let keys = color.get(chars_per_pixel as usize..).unwrap_or_default();
+            keys.split_whitespace()
+                .collect::<Vec<_>>()
+                .windows(2)
+                .any(|pair| pair[0] == "c" && pair[1].eq_ignore_ascii_case("none"))
+        });
+
+        Ok(RasterInfo {
+            format: RasterFormat::Xpm,
+            width,
+            height,
+            bit_depth: None,
+            color_type: ColorType::Indexed,
+            has_alpha,
+        })
+    }
+
+    /// Whether the pixel size of the icon differs from the size of the directory it's in.
+    pub fn mismatches(&self, directory: &SizeDirectory) -> bool {
+        directory
+            .pixel_size()
+            .is_some_and(|size| self.width != size || self.height != size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn png(color_type: u8, extra_chunk: Option<&[u8; 4]>) -> Vec<u8> {
+        let mut bytes = PNG_SIGNATURE.to_vec();
+        let mut chunk = |kind: &[u8; 4], data: &[u8]| {
+            bytes.extend((data.len() as u32).to_be_bytes());
+            bytes.extend(kind);
+            bytes.extend(data);
+            bytes.extend([0; 4]);
+        };
+
+        chunk(b"IHDR", &[0, 0, 0, 22, 0, 0, 0, 22, 8, color_type, 0, 0, 0]);
+        if let Some(kind) = extra_chunk {
+            chunk(kind, &[0, 0]);
+        }
+        chunk(b"IEND", &[]);
+
+        bytes
+    }
+
+    #[test]
+    fn test_parse_png() {
+        let info = RasterInfo::parse_png(&png(6, None)).unwrap();
+
+        assert_eq!((info.width, info.height), (22, 22));
+        assert_eq!(info.bit_depth, Some(8));
+        assert_eq!(info.color_type, ColorType::Rgba);
+        assert!(info.has_alpha);
+
+        let info = RasterInfo::parse_png(&png(3, Some(b"tRNS"))).unwrap();
+        assert_eq!(info.color_type, ColorType::Indexed);
+        assert!(info.has_alpha);
+
+        let info = RasterInfo::parse_png(&png(2, None)).unwrap();
+        assert!(!info.has_alpha);
+    }
+
+    #[test]
+    fn test_parse_xpm() {
+        let source = r#"/* XPM */
+static char *icon[] = {
+"16 15 2 1",
+"  c None",
+". c #000000",
+"................",
+};"#;
+
+        let info = RasterInfo::parse_xpm(source).unwrap();
+
+        assert_eq!((info.width, info.height), (16, 15));
+        assert_eq!(info.bit_depth, None);
+        assert!(info.has_alpha);
+    }
+
+    #[test]
+    fn test_mismatches() {
+        let info = RasterInfo::parse_png(&png(6, None)).unwrap();
+
+        for (directory, expected) in [
+            (SizeDirectory::Fixed { size: 24, scale: 1 }, true),
+            (SizeDirectory::Fixed { size: 22, scale: 1 }, false),
+            (SizeDirectory::Fixed { size: 11, scale: 2 }, false),
+            (SizeDirectory::Scalable, false),
+        ] {
+            assert_eq!(info.mismatches(&directory), expected);
+        }
+    }
+}
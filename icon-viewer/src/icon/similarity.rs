@@ -0,0 +1,138 @@
+/// The size icons are rendered at before hashing.
+pub const HASH_RENDER_SIZE: i32 = 32;
+
+const HASH_WIDTH: usize = 9;
+const HASH_HEIGHT: usize = 8;
+
+/// Borrowed pixel data of a rendered icon.
+pub struct Pixels<'a> {
+    pub data: &'a [u8],
+    pub width: usize,
+    pub height: usize,
+    pub rowstride: usize,
+    pub channels: usize,
+}
+
+impl Pixels<'_> {
+    /// Luminance of the pixel, composited on a white background.
+    fn luminance(&self, x: usize, y: usize) -> f64 {
+        let offset = y * self.rowstride + x * self.channels;
+        let Some(pixel) = self.data.get(offset..offset + self.channels) else {
+            return 255.0;
+        };
+
+        let value = 0.299 * pixel[0] as f64 + 0.587 * pixel[1] as f64 + 0.114 * pixel[2] as f64;
+        let alpha = if self.channels == 4 {
+            pixel[3] as f64 / 255.0
+        } else {
+            1.0
+        };
+
+        value * alpha + 255.0 * (1.0 - alpha)
+    }
+}
+
+/// Computes a 64 bit difference hash of the given pixels.
+///
+/// The image is shrunk to a 9×8 grid of averaged luminance, and every bit tells
+/// whether a cell is brighter than its right neighbour. Icons that look alike end
+/// up with hashes that differ in only a few bits.
+pub fn perceptual_hash(pixels: &Pixels) -> u64 {
+    let mut grid = [[0.0; HASH_WIDTH]; HASH_HEIGHT];
+
+    for (row, cells) in grid.iter_mut().enumerate() {
+        let y_start = row * pixels.height / HASH_HEIGHT;
+        let y_end = ((row + 1) * pixels.height / HASH_HEIGHT).max(y_start + 1);
+
+        for (column, cell) in cells.iter_mut().enumerate() {
+            let x_start = column * pixels.width / HASH_WIDTH;
+            let x_end = ((column + 1) * pixels.width / HASH_WIDTH).max(x_start + 1);
+
+            let mut sum = 0.0;
+            for y in y_start..y_end {
+                for x in x_start..x_end {
+                    sum += pixels.luminance(x, y);
+                }
+            }
+
+            *cell = sum / ((y_end - y_start) * (x_end - x_start)) as f64;
+        }
+    }
+
+    let mut hash = 0;
+    for cells in grid {
+        for pair in cells.windows(2) {
+            hash = hash << 1 | (pair[0] > pair[1]) as u64;
+        }
+    }
+
+    hash
+}
+
+pub fn hamming_distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+/// Returns up to `limit` entries closest to the target hash, nearest first.
+pub fn nearest_hashes(
+    target: u64,
+    hashes: impl IntoIterator<Item = (usize, u64)>,
+    limit: usize,
+) -> Vec<(usize, u32)> {
+    let mut distances: Vec<_> = hashes
+        .into_iter()
+        .map(|(index, hash)| (index, hamming_distance(target, hash)))
+        .collect();
+
+    distances.sort_by_key(|(index, distance)| (*distance, *index));
+    distances.truncate(limit);
+    distances
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(size: usize, from: usize, to: usize) -> Vec<u8> {
+        let mut data = vec![0; size * size * 4];
+        for y in from..to {
+            for x in from..to {
+                let offset = (y * size + x) * 4;
+                data[offset..offset + 4].copy_from_slice(&[0, 0, 0, 255]);
+            }
+        }
+
+        data
+    }
+
+    fn hash(data: &[u8], size: usize) -> u64 {
+        perceptual_hash(&Pixels {
+            data,
+            width: size,
+            height: size,
+            rowstride: size * 4,
+            channels: 4,
+        })
+    }
+
+    #[test]
+    fn test_perceptual_hash() {
+        let small = hash(&square(32, 8, 24), 32);
+        let large = hash(&square(64, 16, 48), 64);
+        let offset = hash(&square(32, 2, 12), 32);
+
+        assert!(hamming_distance(small, large) <= 2);
+        assert!(hamming_distance(small, offset) > 8);
+        assert_eq!(hash(&vec![0; 32 * 32 * 4], 32), 0);
+    }
+
+    #[test]
+    fn test_nearest_hashes() {
+        let hashes = [(0, 0b1111), (1, 0b0000), (2, 0b0111), (3, 0b1110)];
+
+        assert_eq!(
+            nearest_hashes(0b1111, hashes, 3),
+            vec![(0, 0), (2, 1), (3, 1)]
+        );
+    }
+}
@@ -0,0 +1,500 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::{Result, eyre::eyre};
+use gtk::{gdk, glib, graphene, gsk, prelude::*};
+use serde::Serialize;
+
+use super::write_png;
+use crate::icon_theme;
+
+const SVG_NAMESPACE: &str = "http://www.w3.org/2000/svg";
+
+/// The entities XML defines without a DTD.
+const PREDEFINED_ENTITIES: [&str; 5] = ["amp", "lt", "gt", "quot", "apos"];
+
+/// The class every icon of a CSS sprite map has, icons get `icon-<name>` in addition.
+pub const SPRITE_CSS_CLASS: &str = "icon";
+
+/// Where an icon is in a sprite sheet, in pixels of the image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct SpriteRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// The JSON map of a sprite sheet.
+#[derive(Serialize)]
+struct SpriteAtlas<'a> {
+    image: &'a str,
+    width: u32,
+    height: u32,
+    scale: u32,
+    icons: BTreeMap<&'a str, SpriteRect>,
+}
+
+/// Icons of the same size packed into a grid that's as close to a square as possible.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpriteSheet {
+    pub names: Vec<String>,
+    /// The size of every icon in logical pixels.
+    pub size: u32,
+    pub scale: u32,
+    /// The space between and around the icons in logical pixels, which keeps icons from
+    /// bleeding into each other when the image is filtered.
+    pub padding: u32,
+    pub columns: u32,
+}
+
+impl SpriteSheet {
+    pub fn new(names: Vec<String>, size: u32, scale: u32, padding: u32) -> Self {
+        let columns = (names.len() as f64).sqrt().ceil().max(1.0) as u32;
+
+        SpriteSheet {
+            names,
+            size,
+            scale,
+            padding,
+            columns,
+        }
+    }
+
+    pub fn rows(&self) -> u32 {
+        (self.names.len() as u32).div_ceil(self.columns).max(1)
+    }
+
+    /// The width of the image in pixels.
+    pub fn width(&self) -> u32 {
+        (self.columns * (self.size + self.padding) + self.padding) * self.scale
+    }
+
+    /// The height of the image in pixels.
+    pub fn height(&self) -> u32 {
+        (self.rows() * (self.size + self.padding) + self.padding) * self.scale
+    }
+
+    /// The position of the icon at the index in logical pixels.
+    fn position(&self, index: usize) -> (u32, u32) {
+        let (column, row) = (index as u32 % self.columns, index as u32 / self.columns);
+
+        (
+            self.padding + column * (self.size + self.padding),
+            self.padding + row * (self.size + self.padding),
+        )
+    }
+
+    /// The icon names with their place in the image, in the order they're packed.
+    pub fn rects(&self) -> impl Iterator<Item = (&str, SpriteRect)> {
+        self.names.iter().enumerate().map(|(index, name)| {
+            let (x, y) = self.position(index);
+
+            (
+                name.as_str(),
+                SpriteRect {
+                    x: x * self.scale,
+                    y: y * self.scale,
+                    width: self.size * self.scale,
+                    height: self.size * self.scale,
+                },
+            )
+        })
+    }
+
+    /// Maps the icon names to their rectangles in the image of the given file name.
+    pub fn to_json(&self, image: &str) -> Result<String> {
+        let atlas = SpriteAtlas {
+            image,
+            width: self.width(),
+            height: self.height(),
+            scale: self.scale,
+            icons: self.rects().collect(),
+        };
+
+        Ok(serde_json::to_string_pretty(&atlas)? + "\n")
+    }
+
+    /// A stylesheet that shows the icons as backgrounds, with a class per icon.
+    ///
+    /// Sizes are in CSS pixels, so sheets rendered at a higher scale stay sharp.
+    pub fn to_css(&self, image: &str) -> String {
+        let mut css = format!(
+            ".{class} {{\n  display: inline-block;\n  width: {size}px;\n  height: {size}px;\n  \
+             background-image: url(\"{image}\");\n  background-repeat: no-repeat;\n  \
+             background-size: {width}px {height}px;\n}}\n",
+            class = SPRITE_CSS_CLASS,
+            size = self.size,
+            image = image.replace('"', "\\\""),
+            width = self.width() / self.scale,
+            height = self.height() / self.scale,
+        );
+
+        for (index, name) in self.names.iter().enumerate() {
+            let (x, y) = self.position(index);
+            css.push_str(&format!(
+                "\n.{}-{} {{\n  background-position: -{}px -{}px;\n}}\n",
+                SPRITE_CSS_CLASS,
+                css_identifier(name),
+                x,
+                y
+            ));
+        }
+
+        css
+    }
+
+    /// Renders the icons from the current theme into one image and encodes it as PNG.
+    pub fn render(&self, renderer: &gsk::Renderer, palette: &[gdk::RGBA]) -> Result<glib::Bytes> {
+        if self.names.is_empty() || self.size == 0 || self.scale == 0 {
+            return Err(eyre!("Nothing to pack into a sprite sheet"));
+        }
+
+        let bounds = graphene::Rect::new(0.0, 0.0, self.width() as f32, self.height() as f32);
+        let snapshot = gtk::Snapshot::new();
+
+        snapshot.append_color(&gdk::RGBA::TRANSPARENT, &bounds);
+        snapshot.scale(self.scale as f32, self.scale as f32);
+
+        for (index, name) in self.names.iter().enumerate() {
+            let paintable = icon_theme().lookup_icon(
+                name,
+                &[],
+                self.size as i32,
+                self.scale as i32,
+                gtk::TextDirection::Ltr,
+                gtk::IconLookupFlags::empty(),
+            );
+            let (x, y) = self.position(index);
+
+            snapshot.save();
+            snapshot.translate(&graphene::Point::new(x as f32, y as f32));
+            paintable.snapshot_symbolic(&snapshot, self.size as f64, self.size as f64, palette);
+            snapshot.restore();
+        }
+
+        let node = snapshot
+            .to_node()
+            .ok_or_else(|| eyre!("Failed to render the sprite sheet"))?;
+
+        Ok(renderer
+            .render_texture(node, Some(&bounds))
+            .save_to_png_bytes())
+    }
+}
+
+/// An SVG of `<symbol>` elements, see [svg_symbol_sprite].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SvgSprite {
+    pub svg: String,
+    /// The names of the icons that couldn't be added to the sprite.
+    pub rejected: Vec<String>,
+}
+
+/// Combines SVG icons into one SVG of `<symbol>` elements, which pages can show with
+/// `<use href="sprite.svg#icon-name"/>`.
+///
+/// IDs inside the icons are prefixed with the icon name, so gradients and clip paths of
+/// different icons don't clash. The sprite has no DTD, so icons using entities of their
+/// DOCTYPE, and icons binding a namespace prefix to a different namespace than an earlier
+/// icon, are left out, like icons that fail to parse.
+pub fn svg_symbol_sprite(icons: &[(String, String)]) -> Result<SvgSprite> {
+    let mut namespaces = BTreeMap::new();
+    let mut symbols = String::new();
+    let mut rejected = Vec::new();
+
+    for (name, source) in icons {
+        match svg_symbol(name, source, &namespaces) {
+            Ok((symbol, prefixes)) => {
+                namespaces.extend(prefixes);
+                symbols.push_str(&symbol);
+            }
+            Err(err) => {
+                log::warn!("Leaving \"{}\" out of the SVG sprite: {}", name, err);
+                rejected.push(name.clone());
+            }
+        }
+    }
+
+    if rejected.len() == icons.len() {
+        return Err(eyre!("None of the icons can be added to the sprite"));
+    }
+
+    let declarations: String = namespaces
+        .iter()
+        .map(|(prefix, uri)| format!(" xmlns:{}=\"{}\"", prefix, escape_attribute(uri)))
+        .collect();
+
+    Ok(SvgSprite {
+        svg: format!("<svg xmlns=\"{SVG_NAMESPACE}\"{declarations}>\n{symbols}</svg>\n"),
+        rejected,
+    })
+}
+
+/// Converts an icon to a `<symbol>` element, along with the namespace prefixes it binds
+/// that aren't in the given ones yet.
+fn svg_symbol(
+    name: &str,
+    source: &str,
+    namespaces: &BTreeMap<String, String>,
+) -> Result<(String, Vec<(String, String)>)> {
+    let options = roxmltree::ParsingOptions {
+        allow_dtd: true,
+        ..Default::default()
+    };
+    let document = roxmltree::Document::parse_with_options(source, options)?;
+    let root = document.root_element();
+
+    let mut prefixes = Vec::new();
+    for namespace in root.namespaces() {
+        let Some(prefix) = namespace.name().filter(|prefix| *prefix != "xml") else {
+            continue;
+        };
+
+        match namespaces.get(prefix) {
+            None => prefixes.push((prefix.to_string(), namespace.uri().to_string())),
+            Some(uri) if uri != namespace.uri() => {
+                return Err(eyre!(
+                    "It binds the prefix \"{}\" to \"{}\" instead of \"{}\"",
+                    prefix,
+                    namespace.uri(),
+                    uri
+                ));
+            }
+            Some(_) => {}
+        }
+    }
+
+    let view_box = root.attribute("viewBox").map(str::to_string).or_else(|| {
+        let length = |name| root.attribute(name).map(|v: &str| v.trim_end_matches("px"));
+        Some(format!("0 0 {} {}", length("width")?, length("height")?))
+    });
+
+    let content = match (root.first_child(), root.last_child()) {
+        (Some(first), Some(last)) => &source[first.range().start..last.range().end],
+        _ => "",
+    };
+
+    if let Some(entity) = declared_entity(content) {
+        return Err(eyre!(
+            "It uses the entity \"{}\" of its DOCTYPE, which the sprite can't declare",
+            entity
+        ));
+    }
+
+    let ids: Vec<&str> = root
+        .descendants()
+        .skip(1)
+        .filter_map(|node| node.attribute("id"))
+        .collect();
+
+    let symbol = format!(
+        "  <symbol id=\"{}\"{}>{}</symbol>\n",
+        escape_attribute(name),
+        view_box
+            .map(|view_box| format!(" viewBox=\"{}\"", escape_attribute(&view_box)))
+            .unwrap_or_default(),
+        prefix_ids(content, name, &ids)
+    );
+
+    Ok((symbol, prefixes))
+}
+
+/// Writes the PNG of a sprite sheet along with its JSON and CSS maps of the same name.
+pub fn write_sprite_sheet(sheet: &SpriteSheet, png: &[u8], path: &Path) -> Result<()> {
+    let image = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    write_png(path, png)?;
+    std::fs::write(path.with_extension("json"), sheet.to_json(&image)?)?;
+    std::fs::write(path.with_extension("css"), sheet.to_css(&image))?;
+    Ok(())
+}
+
+/// Reads the SVG files of the icons and writes them as one sprite, see [svg_symbol_sprite].
+///
+/// Returns the names of the icons that were left out.
+pub fn write_svg_sprite(icons: &[(String, PathBuf)], path: &Path) -> Result<Vec<String>> {
+    let sources = icons
+        .iter()
+        .map(|(name, file)| Ok((name.clone(), std::fs::read_to_string(file)?)))
+        .collect::<Result<Vec<_>>>()?;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let sprite = svg_symbol_sprite(&sources)?;
+    std::fs::write(path, sprite.svg)?;
+    Ok(sprite.rejected)
+}
+
+/// Prefixes the given IDs and the references to them with the icon name.
+fn prefix_ids(content: &str, name: &str, ids: &[&str]) -> String {
+    let mut content = content.to_string();
+
+    for id in ids {
+        let prefixed = format!("{name}-{id}");
+
+        for (from, to) in [
+            (format!("id=\"{id}\""), format!("id=\"{prefixed}\"")),
+            (format!("id='{id}'"), format!("id='{prefixed}'")),
+            (format!("url(#{id})"), format!("url(#{prefixed})")),
+            (format!("\"#{id}\""), format!("\"#{prefixed}\"")),
+            (format!("'#{id}'"), format!("'#{prefixed}'")),
+        ] {
+            content = content.replace(&from, &to);
+        }
+    }
+
+    content
+}
+
+/// Finds a reference to an entity that isn't predefined, so has to be declared by a DTD.
+fn declared_entity(content: &str) -> Option<&str> {
+    content.match_indices('&').find_map(|(start, _)| {
+        let rest = &content[start + 1..];
+        let entity = &rest[..rest.find(';')?];
+
+        (!entity.starts_with('#') && !PREDEFINED_ENTITIES.contains(&entity)).then_some(entity)
+    })
+}
+
+fn escape_attribute(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+}
+
+/// Escapes the characters of an icon name that aren't allowed in a CSS class name.
+fn css_identifier(name: &str) -> String {
+    let mut identifier = String::with_capacity(name.len());
+
+    for c in name.chars() {
+        if !(c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+            identifier.push('\\');
+        }
+        identifier.push(c);
+    }
+
+    identifier
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sprite_sheet() {
+        let names: Vec<String> = ["a", "b", "c", "org.gnome.Nautilus", "e"]
+            .iter()
+            .map(|name| name.to_string())
+            .collect();
+        let sheet = SpriteSheet::new(names, 16, 2, 1);
+
+        assert_eq!((sheet.columns, sheet.rows()), (3, 2));
+        assert_eq!((sheet.width(), sheet.height()), (104, 70));
+
+        let rects: Vec<_> = sheet.rects().collect();
+        assert_eq!(
+            rects[4],
+            (
+                "e",
+                SpriteRect {
+                    x: 36,
+                    y: 36,
+                    width: 32,
+                    height: 32
+                }
+            )
+        );
+
+        let json: serde_json::Value =
+            serde_json::from_str(&sheet.to_json("icons.png").unwrap()).unwrap();
+        assert_eq!(json["icons"]["b"]["x"], 36);
+        assert_eq!(json["scale"], 2);
+
+        let css = sheet.to_css("icons.png");
+        assert!(css.contains("background-size: 52px 35px;"));
+        assert!(css.contains(".icon-org\\.gnome\\.Nautilus {\n  background-position: -1px -18px;"));
+    }
+
+    #[test]
+    fn test_svg_symbol_sprite() {
+        let icons = [
+            (
+                String::from("edit-copy"),
+                String::from(
+                    r##"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" viewBox="0 0 16 16"><defs><linearGradient id="a"/></defs><path fill="url(#a)" d="M0 0h16v16z"/><use xlink:href="#a"/></svg>"##,
+                ),
+            ),
+            (
+                String::from("edit-cut"),
+                String::from(
+                    r#"<svg xmlns="http://www.w3.org/2000/svg" width="24px" height="24px"><circle r="4"/></svg>"#,
+                ),
+            ),
+        ];
+
+        let sprite = svg_symbol_sprite(&icons).unwrap().svg;
+        assert!(sprite.contains("xmlns:xlink=\"http://www.w3.org/1999/xlink\""));
+        assert!(sprite.contains("<symbol id=\"edit-copy\" viewBox=\"0 0 16 16\"><defs>"));
+        assert!(sprite.contains("id=\"edit-copy-a\"") && sprite.contains("url(#edit-copy-a)"));
+        assert!(sprite.contains("xlink:href=\"#edit-copy-a\""));
+        assert!(sprite.contains("<symbol id=\"edit-cut\" viewBox=\"0 0 24 24\"><circle r=\"4\"/>"));
+
+        let document = roxmltree::Document::parse(&sprite).unwrap();
+        assert_eq!(
+            document
+                .descendants()
+                .filter(|node| node.has_tag_name("symbol"))
+                .count(),
+            2
+        );
+
+        let bad = (String::from("bad"), String::from("<svg"));
+        assert!(svg_symbol_sprite(std::slice::from_ref(&bad)).is_err());
+
+        let sprite = svg_symbol_sprite(&[icons[0].clone(), bad]).unwrap();
+        assert_eq!(sprite.rejected, vec!["bad"]);
+    }
+
+    #[test]
+    fn test_svg_symbol_sprite_conflicts() {
+        let icon = |name: &str, source: &str| (name.to_string(), source.to_string());
+
+        // Entities of the DOCTYPE are fine where they aren't copied into the sprite
+        let illustrator = icon(
+            "illustrator",
+            r#"<!DOCTYPE svg [<!ENTITY ns_svg "http://www.w3.org/2000/svg">]><svg xmlns="&ns_svg;" viewBox="0 0 16 16"><path d="M0 0h16"/><text>&amp;</text></svg>"#,
+        );
+        let sprite = svg_symbol_sprite(std::slice::from_ref(&illustrator)).unwrap();
+        assert!(sprite.rejected.is_empty());
+
+        let entity = icon(
+            "entity",
+            r#"<!DOCTYPE svg [<!ENTITY fill "red">]><svg xmlns="http://www.w3.org/2000/svg"><path fill="&fill;"/></svg>"#,
+        );
+        let sprite = svg_symbol_sprite(&[illustrator, entity]).unwrap();
+        assert_eq!(sprite.rejected, vec!["entity"]);
+        assert!(sprite.svg.contains("<symbol id=\"illustrator\""));
+
+        let prefix = |name: &str, uri: &str| {
+            icon(
+                name,
+                &format!(r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:a="{uri}"/>"#),
+            )
+        };
+        let sprite = svg_symbol_sprite(&[prefix("a", "urn:a"), prefix("b", "urn:a")]).unwrap();
+        assert!(sprite.rejected.is_empty());
+
+        let sprite = svg_symbol_sprite(&[prefix("a", "urn:a"), prefix("b", "urn:b")]).unwrap();
+        assert_eq!(sprite.rejected, vec!["b"]);
+        assert!(sprite.svg.contains("xmlns:a=\"urn:a\""));
+    }
+}
@@ -0,0 +1,347 @@
+use std::{ops::Range, path::Path};
+
+use color_eyre::Result;
+
+const INKSCAPE_NAMESPACE: &str = "http://www.inkscape.org/namespaces/inkscape";
+const DUBLIN_CORE_NAMESPACE: &str = "http://purl.org/dc/elements/1.1/";
+
+/// Elements whose text describes the icon.
+const TEXT_ELEMENTS: &[&str] = &["title", "desc", "metadata"];
+
+/// Dublin Core elements that editors fill in for every drawing, like the `image/svg+xml`
+/// format, which say nothing about the icon.
+const DUBLIN_CORE_BOILERPLATE: &[&str] = &["format", "type", "date", "language"];
+
+/// Summary of an SVG document, used by the source inspector.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SvgInfo {
+    pub view_box: Option<String>,
+    pub width: Option<String>,
+    pub height: Option<String>,
+    pub element_count: usize,
+    pub path_count: usize,
+    pub embedded_images: usize,
+    pub external_references: Vec<String>,
+    pub file_size: u64,
+}
+
+impl SvgInfo {
+    /// Parses the given SVG source and collects information about it.
+    pub fn parse(source: &str) -> Result<Self> {
+        let document = parse_document(source)?;
+        let root = document.root_element();
+
+        let mut info = SvgInfo {
+            view_box: root.attribute("viewBox").map(str::to_string),
+            width: root.attribute("width").map(str::to_string),
+            height: root.attribute("height").map(str::to_string),
+            file_size: source.len() as u64,
+            ..Default::default()
+        };
+
+        for node in root.descendants().filter(|n| n.is_element()) {
+            info.element_count += 1;
+
+            let name = node.tag_name().name();
+            if name == "path" {
+                info.path_count += 1;
+            }
+
+            let Some(href) = href(&node) else {
+                continue;
+            };
+
+            if href.starts_with("data:image/") {
+                if name == "image" {
+                    info.embedded_images += 1;
+                }
+            } else if !href.starts_with('#') && !href.starts_with("data:") {
+                info.external_references.push(href.to_string());
+            }
+        }
+
+        Ok(info)
+    }
+}
+
+/// Reads the SVG file at the given path, returning its source and [SvgInfo].
+pub fn read_svg(path: &Path) -> Result<(String, SvgInfo)> {
+    let source = std::fs::read_to_string(path)?;
+    let mut info = SvgInfo::parse(&source)?;
+    info.file_size = std::fs::metadata(path)?.len();
+
+    Ok((source, info))
+}
+
+/// Extracts the descriptive text of an SVG document, without duplicates.
+///
+/// This is the text of its `title`, `desc` and `metadata` elements, including RDF
+/// keywords, and the values of `inkscape:label` attributes. Boilerplate like the format
+/// of the file and default layer names like "Layer 1" is left out.
+pub fn parse_svg_metadata(source: &str) -> Result<Vec<String>> {
+    let document = parse_document(source)?;
+    let mut metadata: Vec<String> = Vec::new();
+
+    for node in document.root_element().descendants().filter(|n| n.is_element()) {
+        let label = node
+            .attribute((INKSCAPE_NAMESPACE, "label"))
+            .filter(|label| !is_default_label(label));
+
+        let text = TEXT_ELEMENTS
+            .contains(&node.tag_name().name())
+            .then(|| {
+                node.descendants()
+                    .filter(|n| n.is_text() && !is_boilerplate(n))
+                    .filter_map(|n| n.text())
+            })
+            .into_iter()
+            .flatten();
+
+        for value in label.into_iter().chain(text).map(str::trim) {
+            if !value.is_empty() && !metadata.iter().any(|m| m == value) {
+                metadata.push(value.to_string());
+            }
+        }
+    }
+
+    Ok(metadata)
+}
+
+/// Whether the text is inside a Dublin Core element of [DUBLIN_CORE_BOILERPLATE].
+fn is_boilerplate(node: &roxmltree::Node) -> bool {
+    node.ancestors().any(|ancestor| {
+        ancestor.tag_name().namespace() == Some(DUBLIN_CORE_NAMESPACE)
+            && DUBLIN_CORE_BOILERPLATE.contains(&ancestor.tag_name().name())
+    })
+}
+
+/// Whether the label is the name Inkscape gives new layers, e.g. "Layer 1".
+fn is_default_label(label: &str) -> bool {
+    label
+        .trim()
+        .strip_prefix("Layer ")
+        .is_some_and(|number| number.parse::<u32>().is_ok())
+}
+
+/// Reads the SVG file at the given path, returning its descriptive text.
+pub fn read_svg_metadata(path: &Path) -> Result<Vec<String>> {
+    parse_svg_metadata(&std::fs::read_to_string(path)?)
+}
+
+/// Parses an SVG document, allowing the DOCTYPE that Inkscape and Illustrator often add.
+fn parse_document(source: &str) -> Result<roxmltree::Document<'_>, roxmltree::Error> {
+    let options = roxmltree::ParsingOptions {
+        allow_dtd: true,
+        ..Default::default()
+    };
+    roxmltree::Document::parse_with_options(source, options)
+}
+
+fn href<'a>(node: &roxmltree::Node<'a, '_>) -> Option<&'a str> {
+    node.attributes()
+        .find(|a| a.name() == "href")
+        .map(|a| a.value())
+}
+
+/// The kind of a highlighted span in XML source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum XmlToken {
+    Tag,
+    Attribute,
+    Value,
+    Comment,
+    Declaration,
+}
+
+impl XmlToken {
+    pub fn name(&self) -> &'static str {
+        match self {
+            XmlToken::Tag => "tag",
+            XmlToken::Attribute => "attribute",
+            XmlToken::Value => "value",
+            XmlToken::Comment => "comment",
+            XmlToken::Declaration => "declaration",
+        }
+    }
+}
+
+/// Splits XML source into highlighted spans.
+///
+/// The ranges are in characters rather than bytes, so they can be used directly
+/// with [gtk::TextBuffer] offsets.
+pub fn highlight_xml(source: &str) -> Vec<(Range<usize>, XmlToken)> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    let starts_with = |i: usize, s: &str| {
+        s.chars()
+            .enumerate()
+            .all(|(o, c)| chars.get(i + o) == Some(&c))
+    };
+    let find = |from: usize, s: &str| {
+        (from..chars.len())
+            .find(|&i| starts_with(i, s))
+            .map(|i| i + s.chars().count())
+            .unwrap_or(chars.len())
+    };
+
+    while i < chars.len() {
+        if chars[i] != '<' {
+            i += 1;
+            continue;
+        }
+
+        if starts_with(i, "<!--") {
+            let end = find(i, "-->");
+            tokens.push((i..end, XmlToken::Comment));
+            i = end;
+            continue;
+        }
+
+        if starts_with(i, "<?") || starts_with(i, "<!") {
+            let end = find(i, ">");
+            tokens.push((i..end, XmlToken::Declaration));
+            i = end;
+            continue;
+        }
+
+        let start = i;
+        i += 1;
+        if chars.get(i) == Some(&'/') {
+            i += 1;
+        }
+        while i < chars.len() && !chars[i].is_whitespace() && !matches!(chars[i], '>' | '/') {
+            i += 1;
+        }
+        tokens.push((start..i, XmlToken::Tag));
+
+        while i < chars.len() && chars[i] != '>' {
+            let c = chars[i];
+            if c == '"' || c == '\'' {
+                let value_start = i;
+                i += 1;
+                while i < chars.len() && chars[i] != c {
+                    i += 1;
+                }
+                i = (i + 1).min(chars.len());
+                tokens.push((value_start..i, XmlToken::Value));
+            } else if c.is_whitespace() || c == '=' {
+                i += 1;
+            } else if c == '/' {
+                tokens.push((i..i + 1, XmlToken::Tag));
+                i += 1;
+            } else {
+                let attribute_start = i;
+                while i < chars.len()
+                    && !chars[i].is_whitespace()
+                    && !matches!(chars[i], '=' | '>' | '/')
+                {
+                    i += 1;
+                }
+                tokens.push((attribute_start..i, XmlToken::Attribute));
+            }
+        }
+
+        if i < chars.len() {
+            tokens.push((i..i + 1, XmlToken::Tag));
+            i += 1;
+        }
+    }
+
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r##"<?xml version="1.0"?>
+<!-- icon -->
+<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" width="16" height="16" viewBox="0 0 16 16">
+  <g>
+    <path d="M0 0h16v16H0z"/>
+    <path d="M4 4h8v8H4z"/>
+    <use xlink:href="#a"/>
+    <image href="data:image/png;base64,AAAA"/>
+    <image xlink:href="../pictures/photo.png"/>
+  </g>
+</svg>
+"##;
+
+    #[test]
+    fn test_parse_svg_info() {
+        let info = SvgInfo::parse(SOURCE).unwrap();
+
+        assert_eq!(info.view_box.as_deref(), Some("0 0 16 16"));
+        assert_eq!(info.width.as_deref(), Some("16"));
+        assert_eq!(info.height.as_deref(), Some("16"));
+        assert_eq!(info.element_count, 7);
+        assert_eq!(info.path_count, 2);
+        assert_eq!(info.embedded_images, 1);
+        assert_eq!(info.external_references, vec!["../pictures/photo.png"]);
+    }
+
+    #[test]
+    fn test_parse_svg_info_with_doctype() {
+        let source = r#"<?xml version="1.0" encoding="utf-8"?>
+<!DOCTYPE svg PUBLIC "-//W3C//DTD SVG 1.1//EN" "http://www.w3.org/Graphics/SVG/1.1/DTD/svg11.dtd" [
+  <!ENTITY ns_svg "http://www.w3.org/2000/svg">
+]>
+<svg xmlns="&ns_svg;" width="16" height="16"><path d="M0 0h16v16H0z"/></svg>
+"#;
+        let info = SvgInfo::parse(source).unwrap();
+
+        assert_eq!(info.width.as_deref(), Some("16"));
+        assert_eq!(info.element_count, 2);
+        assert_eq!(info.path_count, 1);
+    }
+
+    #[test]
+    fn test_parse_svg_metadata() {
+        let source = r#"<!DOCTYPE svg PUBLIC "-//W3C//DTD SVG 1.1//EN" "svg11.dtd">
+<svg xmlns="http://www.w3.org/2000/svg"
+     xmlns:inkscape="http://www.inkscape.org/namespaces/inkscape"
+     xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"
+     xmlns:dc="http://purl.org/dc/elements/1.1/">
+  <title>Recycle Bin</title>
+  <desc> An empty trash can </desc>
+  <metadata>
+    <rdf:RDF>
+      <dc:format>image/svg+xml</dc:format>
+      <dc:title>Recycle Bin</dc:title>
+      <dc:subject><rdf:Bag><rdf:li>trash</rdf:li><rdf:li>delete</rdf:li></rdf:Bag></dc:subject>
+    </rdf:RDF>
+  </metadata>
+  <g inkscape:label="Layer 1" inkscape:groupmode="layer">
+    <g inkscape:label="Lid"><path d="M0 0h16v16H0z"/></g>
+  </g>
+</svg>"#;
+
+        assert_eq!(
+            parse_svg_metadata(source).unwrap(),
+            vec!["Recycle Bin", "An empty trash can", "trash", "delete", "Lid"]
+        );
+    }
+
+    #[test]
+    fn test_highlight_xml() {
+        let source = r#"<!-- a --><path d="M0 0"/>"#;
+        let tokens: Vec<_> = highlight_xml(source)
+            .into_iter()
+            .map(|(range, token)| (source[range].to_string(), token))
+            .collect();
+
+        assert_eq!(
+            tokens,
+            vec![
+                ("<!-- a -->".to_string(), XmlToken::Comment),
+                ("<path".to_string(), XmlToken::Tag),
+                ("d".to_string(), XmlToken::Attribute),
+                ("\"M0 0\"".to_string(), XmlToken::Value),
+                ("/".to_string(), XmlToken::Tag),
+                (">".to_string(), XmlToken::Tag),
+            ]
+        );
+    }
+}
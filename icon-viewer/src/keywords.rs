@@ -0,0 +1,105 @@
+use std::collections::BTreeMap;
+
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+
+use super::load_config;
+
+const KEYWORDS_FILE: &str = "keywords.toml";
+const BUNDLED_KEYWORDS: &str = include_str!("../data/keywords.toml");
+
+/// Human-language keywords and synonyms for icon names, so icons can be searched by concept.
+///
+/// An entry applies to the icon of the same name, and to every icon whose name continues
+/// it after a dash, so `network-wireless` covers `network-wireless-signal-good-symbolic`.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct KeywordDatabase {
+    pub entries: BTreeMap<String, Vec<String>>,
+}
+
+impl KeywordDatabase {
+    /// The keywords shipped with the application.
+    pub fn bundled() -> Self {
+        Self::parse(BUNDLED_KEYWORDS).expect("Bundled keywords are invalid")
+    }
+
+    /// Loads the bundled keywords, merged with the user's keywords file if it exists.
+    pub fn load() -> Result<Self> {
+        let mut database = Self::bundled();
+
+        if let Some(user_keywords) = load_config(KEYWORDS_FILE)? {
+            database.merge(user_keywords);
+        }
+
+        Ok(database)
+    }
+
+    pub fn parse(source: &str) -> Result<Self> {
+        Ok(toml::from_str(source)?)
+    }
+
+    /// Adds the entries of another database, replacing the entries with the same name.
+    pub fn merge(&mut self, other: KeywordDatabase) {
+        self.entries.extend(other.entries);
+    }
+
+    /// The keywords of every entry that applies to the icon name, without duplicates.
+    pub fn keywords_for(&self, name: &str) -> Vec<String> {
+        let mut keywords: Vec<String> = Vec::new();
+
+        let prefixes = name
+            .match_indices('-')
+            .map(|(index, _)| &name[..index])
+            .chain([name]);
+
+        for keyword in prefixes.filter_map(|prefix| self.entries.get(prefix)).flatten() {
+            if !keywords.contains(keyword) {
+                keywords.push(keyword.clone());
+            }
+        }
+
+        keywords
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keywords_for() {
+        let mut database = KeywordDatabase::parse(
+            r#"
+            "network-wireless" = ["wifi", "wireless"]
+            "network-wireless-offline" = ["disconnected", "wifi"]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            database.keywords_for("network-wireless-offline-symbolic"),
+            vec!["wifi", "wireless", "disconnected"]
+        );
+        assert!(database.keywords_for("network-wired").is_empty());
+        assert!(database.keywords_for("network-wirelessly").is_empty());
+
+        database.merge(
+            KeywordDatabase::parse(
+                r#"
+                "network-wired" = ["ethernet"]
+                "network-wireless" = ["wlan"]
+                "#,
+            )
+            .unwrap(),
+        );
+        assert_eq!(database.keywords_for("network-wired"), vec!["ethernet"]);
+        assert_eq!(
+            database.keywords_for("network-wireless-offline"),
+            vec!["wlan", "disconnected", "wifi"]
+        );
+
+        let bundled = KeywordDatabase::bundled();
+        assert!(bundled.keywords_for("user-trash-full").contains(&String::from("trash")));
+    }
+}
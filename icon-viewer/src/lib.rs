@@ -1,11 +1,35 @@
+use std::path::PathBuf;
+
+use color_eyre::{Result, eyre::eyre};
 use gtk::{IconTheme};
+use serde::{Serialize, de::DeserializeOwned};
 
 pub mod icon;
 mod selector;
+mod collection;
+mod favorites;
 mod filter_widget;
+mod keywords;
+mod matcher;
+mod query;
+mod saved_search;
+mod snippets;
+mod sort;
+mod stats;
+mod tag_filter;
 
+pub use collection::*;
+pub use favorites::*;
 pub use filter_widget::*;
+pub use keywords::*;
+pub use matcher::*;
+pub use query::*;
+pub use saved_search::*;
+pub use snippets::*;
 pub use selector::*;
+pub use sort::*;
+pub use stats::*;
+pub use tag_filter::*;
 
 pub const CATEGORIES: &[(&str, &str)] = &[
     ("Actions", "actions"),
@@ -21,6 +45,42 @@ pub const CATEGORIES: &[(&str, &str)] = &[
     ("Status", "status"),
 ];
 
+/// File formats that can be filtered by, see [icon_format].
+pub const FORMATS: &[(&str, &str)] = &[
+    ("SVG", "svg"),
+    ("PNG", "png"),
+    ("XPM", "xpm"),
+    ("Embedded", "embedded"),
+    ("Other", "other"),
+];
+
 pub fn icon_theme() -> IconTheme {
     IconTheme::for_display(&gtk::gdk::Display::default().expect("Failed to get display"))
 }
+
+/// The directory the configuration files of the application are stored in.
+pub fn config_dir() -> Option<PathBuf> {
+    directories::ProjectDirs::from("codes", "blaine", "NettIconViewer")
+        .map(|dirs| dirs.config_dir().to_path_buf())
+}
+
+/// Loads a TOML file from the [config_dir], [None] if it doesn't exist yet.
+pub fn load_config<T: DeserializeOwned>(file: &str) -> Result<Option<T>> {
+    let Some(path) = config_dir().map(|dir| dir.join(file)) else {
+        return Ok(None);
+    };
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    Ok(Some(toml::from_str(&std::fs::read_to_string(path)?)?))
+}
+
+/// Saves the value as a TOML file in the [config_dir], creating the directory if needed.
+pub fn save_config<T: Serialize>(file: &str, value: &T) -> Result<()> {
+    let dir = config_dir().ok_or_else(|| eyre!("No config directory"))?;
+    std::fs::create_dir_all(&dir)?;
+
+    std::fs::write(dir.join(file), toml::to_string_pretty(value)?)?;
+    Ok(())
+}
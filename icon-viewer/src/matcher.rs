@@ -0,0 +1,236 @@
+use fuzzy_matcher::{FuzzyMatcher, skim::SkimMatcherV2};
+use gtk::glib;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// How the free text of a search is matched against icon names.
+#[derive(
+    Copy, Clone, Debug, Eq, PartialEq, Hash, Default, glib::Enum, Serialize, Deserialize,
+)]
+#[enum_type(name = "NettIconViewerSearchMode")]
+#[serde(rename_all = "lowercase")]
+pub enum SearchMode {
+    #[default]
+    Fuzzy,
+    Exact,
+    Prefix,
+    Substring,
+    Regex,
+}
+
+impl SearchMode {
+    /// All modes, in the order they are listed in the selector.
+    pub const ALL: [SearchMode; 5] = [
+        SearchMode::Fuzzy,
+        SearchMode::Exact,
+        SearchMode::Prefix,
+        SearchMode::Substring,
+        SearchMode::Regex,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SearchMode::Fuzzy => "Fuzzy",
+            SearchMode::Exact => "Exact",
+            SearchMode::Prefix => "Prefix",
+            SearchMode::Substring => "Substring",
+            SearchMode::Regex => "Regex",
+        }
+    }
+
+    /// Whether searching for the text only matches what searching for the previous text
+    /// matched too, like after typing another character.
+    pub fn narrows(&self, text: &str, previous: &str) -> bool {
+        previous.is_empty()
+            || matches!(
+                self,
+                SearchMode::Fuzzy | SearchMode::Prefix | SearchMode::Substring
+            ) && text.starts_with(previous)
+    }
+}
+
+/// Matches text against a search pattern using one of the [SearchMode]s.
+///
+/// Everything but [SearchMode::Regex] ignores case, regexes can opt in with `(?i)`.
+#[derive(Default)]
+pub struct TextMatcher {
+    mode: SearchMode,
+    text: String,
+    pattern: Vec<char>,
+    regex: Option<Regex>,
+    fuzzy: SkimMatcherV2,
+}
+
+impl TextMatcher {
+    pub fn new(mode: SearchMode, pattern: &str) -> Self {
+        let regex = match mode {
+            SearchMode::Regex if !pattern.is_empty() => match Regex::new(pattern) {
+                Ok(regex) => Some(regex),
+                Err(err) => {
+                    log::debug!("Invalid search regex \"{}\": {}", pattern, err);
+                    None
+                }
+            },
+            _ => None,
+        };
+
+        TextMatcher {
+            mode,
+            text: pattern.to_string(),
+            pattern: fold_case(pattern),
+            regex,
+            fuzzy: SkimMatcherV2::default(),
+        }
+    }
+
+    pub fn mode(&self) -> SearchMode {
+        self.mode
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pattern.is_empty()
+    }
+
+    /// Scores how well the text matches, higher is better.
+    ///
+    /// Returns [None] if the text doesn't match. An empty pattern matches everything.
+    pub fn score(&self, text: &str) -> Option<i64> {
+        if self.is_empty() {
+            return Some(0);
+        }
+
+        if self.mode == SearchMode::Fuzzy {
+            return self.fuzzy.fuzzy_match(text, &self.text);
+        }
+
+        let length = text.chars().count().max(1) as i64;
+        let (start, end) = self.find(text)?;
+
+        // Prefer matches covering more of the text, then matches closer to the start
+        Some((end - start) as i64 * 1000 / length - start as i64)
+    }
+
+    /// Returns the best matching candidate and its score.
+    pub fn best_match<'a>(
+        &self,
+        candidates: impl IntoIterator<Item = &'a str>,
+    ) -> Option<(&'a str, i64)> {
+        candidates
+            .into_iter()
+            .filter_map(|candidate| Some((candidate, self.score(candidate)?)))
+            .max_by_key(|(_, score)| *score)
+    }
+
+    /// Returns the char indices of the matched characters, used for highlighting.
+    pub fn indices(&self, text: &str) -> Option<Vec<usize>> {
+        if self.is_empty() {
+            return Some(Vec::new());
+        }
+
+        if self.mode == SearchMode::Fuzzy {
+            return self
+                .fuzzy
+                .fuzzy_indices(text, &self.text)
+                .map(|(_, indices)| indices);
+        }
+
+        self.find(text).map(|(start, end)| (start..end).collect())
+    }
+
+    /// Finds the char range of the first match in the text.
+    fn find(&self, text: &str) -> Option<(usize, usize)> {
+        let pattern = &self.pattern;
+
+        match self.mode {
+            SearchMode::Fuzzy => None,
+            SearchMode::Exact => (fold_case(text) == *pattern).then_some((0, pattern.len())),
+            SearchMode::Prefix => fold_case(text)
+                .starts_with(pattern)
+                .then_some((0, pattern.len())),
+            SearchMode::Substring => fold_case(text)
+                .windows(pattern.len())
+                .position(|window| window == pattern.as_slice())
+                .map(|start| (start, start + pattern.len())),
+            SearchMode::Regex => {
+                let found = self.regex.as_ref()?.find(text)?;
+                let start = text[..found.start()].chars().count();
+
+                Some((start, start + found.as_str().chars().count()))
+            }
+        }
+    }
+}
+
+/// Lowercases every character, keeping the char indices of the text intact.
+fn fold_case(text: &str) -> Vec<char> {
+    text.chars()
+        .map(|c| c.to_lowercase().next().unwrap_or(c))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_modes() {
+        let names = ["go-home", "google-chrome", "emblem-ok", "GO"];
+
+        for (mode, pattern, expected) in [
+            (SearchMode::Exact, "go", vec![false, false, false, true]),
+            (SearchMode::Prefix, "go", vec![true, true, false, true]),
+            (SearchMode::Substring, "ok", vec![false, false, true, false]),
+            (
+                SearchMode::Regex,
+                "^go(-|$)",
+                vec![true, false, false, false],
+            ),
+            (SearchMode::Regex, "(", vec![false, false, false, false]),
+        ] {
+            let matcher = TextMatcher::new(mode, pattern);
+            let matches: Vec<_> = names
+                .iter()
+                .map(|name| matcher.score(name).is_some())
+                .collect();
+
+            assert_eq!(matches, expected, "{mode:?} {pattern}");
+        }
+    }
+
+    #[test]
+    fn test_indices_and_score() {
+        let matcher = TextMatcher::new(SearchMode::Substring, "home");
+        assert_eq!(matcher.indices("go-home"), Some(vec![3, 4, 5, 6]));
+        assert!(matcher.score("home").unwrap() > matcher.score("go-home").unwrap());
+
+        let matcher = TextMatcher::new(SearchMode::Regex, "é+");
+        assert_eq!(matcher.indices("caféé"), Some(vec![3, 4]));
+
+        assert_eq!(TextMatcher::new(SearchMode::Exact, "").score("x"), Some(0));
+    }
+
+    #[test]
+    fn test_narrows() {
+        assert!(SearchMode::Fuzzy.narrows("go-h", "go"));
+        assert!(SearchMode::Substring.narrows("go", "go"));
+        assert!(!SearchMode::Prefix.narrows("go", "go-h"));
+        assert!(!SearchMode::Exact.narrows("go-h", "go"));
+        assert!(!SearchMode::Regex.narrows("go-h", "go"));
+        assert!(SearchMode::Regex.narrows("(", ""));
+    }
+
+    #[test]
+    fn test_best_match() {
+        let aliases = ["gtk-ok", "gtk-save", "document-save-as"];
+
+        let matcher = TextMatcher::new(SearchMode::Substring, "save");
+        assert_eq!(
+            matcher.best_match(aliases).map(|(a, _)| a),
+            Some("gtk-save")
+        );
+
+        let matcher = TextMatcher::new(SearchMode::Exact, "gtk-ok");
+        assert_eq!(matcher.best_match(aliases).map(|(a, _)| a), Some("gtk-ok"));
+        assert_eq!(matcher.best_match(["gtk-okay"]), None);
+    }
+}
@@ -0,0 +1,477 @@
+use regex::Regex;
+
+use super::{
+    CATEGORIES,
+    icon::{IconData, SizeDirectory},
+    icon_format,
+};
+
+/// Matches an icon name against a plain substring or a `/regex/`.
+#[derive(Debug, Clone)]
+pub enum NamePattern {
+    Contains(String),
+    Regex(Regex),
+}
+
+impl PartialEq for NamePattern {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (NamePattern::Contains(a), NamePattern::Contains(b)) => a == b,
+            (NamePattern::Regex(a), NamePattern::Regex(b)) => a.as_str() == b.as_str(),
+            _ => false,
+        }
+    }
+}
+
+impl NamePattern {
+    fn parse(value: &str) -> Self {
+        if let Some(pattern) = value
+            .strip_prefix('/')
+            .and_then(|v| v.strip_suffix('/'))
+            .filter(|v| !v.is_empty())
+        {
+            match Regex::new(pattern) {
+                Ok(regex) => return NamePattern::Regex(regex),
+                Err(err) => log::debug!("Invalid name pattern \"{}\": {}", pattern, err),
+            }
+        }
+
+        NamePattern::Contains(value.to_lowercase())
+    }
+
+    fn matches(&self, name: &str) -> bool {
+        match self {
+            NamePattern::Contains(text) => name.to_lowercase().contains(text),
+            NamePattern::Regex(regex) => regex.is_match(name),
+        }
+    }
+}
+
+/// A single condition an icon has to meet to be displayed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Predicate {
+    Not(Box<Predicate>),
+    /// Matches if any of the inner predicates match.
+    Any(Vec<Predicate>),
+    Symbolic,
+    Symlink,
+    Embedded,
+    Duplicate,
+    Favorite,
+    HasAliases,
+    /// A symlink that doesn't point to another icon of the theme.
+    Unlinked,
+    /// Matches the value of a category in [CATEGORIES], or `unknown`.
+    Context(String),
+    Size {
+        size: u32,
+        scale: Option<u32>,
+    },
+    /// Matches the size directory of the icon, [None] matches icons outside of one.
+    SizeDirectory(Option<SizeDirectory>),
+    /// Matches the value of a format in [super::FORMATS].
+    Format(String),
+    Theme(String),
+    Tag(String),
+    Name(NamePattern),
+}
+
+impl Predicate {
+    /// Creates a predicate from a `key:value` pair, returns [None] if the key is unknown.
+    pub fn parse(key: &str, value: &str) -> Option<Self> {
+        let flag = |predicate: Predicate| match value.to_lowercase().as_str() {
+            // Matches every icon, e.g. to show symlinks, which are hidden by default
+            "any" => Predicate::Any(vec![predicate.clone(), Predicate::Not(Box::new(predicate))]),
+            _ if parse_bool(value) => predicate,
+            _ => Predicate::Not(Box::new(predicate)),
+        };
+
+        let predicate = match key {
+            "ctx" => Predicate::Context(context_value(value)),
+            "size" => parse_size(value)?,
+            "symbolic" => flag(Predicate::Symbolic),
+            "symlink" => flag(Predicate::Symlink),
+            "embedded" => flag(Predicate::Embedded),
+            "duplicate" => flag(Predicate::Duplicate),
+            "favorite" => flag(Predicate::Favorite),
+            "alias" => flag(Predicate::HasAliases),
+            "format" => Predicate::Format(value.to_lowercase()),
+            "theme" => Predicate::Theme(value.to_lowercase()),
+            "tag" => Predicate::Tag(value.to_lowercase()),
+            "name" => Predicate::Name(NamePattern::parse(value)),
+            _ => return None,
+        };
+
+        Some(predicate)
+    }
+
+    /// Whether every icon matching this predicate also matches the other one.
+    ///
+    /// Only recognizes equal predicates and [Predicate::Any] lists that drop alternatives,
+    /// like unticking one category.
+    pub fn implies(&self, other: &Predicate) -> bool {
+        match (self, other) {
+            (Predicate::Any(predicates), Predicate::Any(_)) => {
+                predicates.iter().all(|predicate| predicate.implies(other))
+            }
+            (predicate, Predicate::Any(others)) => {
+                others.iter().any(|other| predicate.implies(other))
+            }
+            _ => self == other,
+        }
+    }
+
+    /// Whether the predicate is, or is nested in, this one.
+    pub fn contains(&self, predicate: &Predicate) -> bool {
+        match self {
+            Predicate::Not(inner) => inner.contains(predicate),
+            Predicate::Any(predicates) => predicates.iter().any(|p| p.contains(predicate)),
+            _ => self == predicate,
+        }
+    }
+
+    pub fn matches(&self, icon: &IconData) -> bool {
+        match self {
+            Predicate::Not(predicate) => !predicate.matches(icon),
+            Predicate::Any(predicates) => predicates.iter().any(|p| p.matches(icon)),
+            Predicate::Symbolic => icon.is_symbolic,
+            Predicate::Symlink => icon.is_symlink,
+            Predicate::Embedded => icon.is_embedded,
+            Predicate::Duplicate => !icon.duplicates.is_empty(),
+            Predicate::Favorite => icon.is_favorite,
+            Predicate::HasAliases => !icon.aliases.is_empty(),
+            Predicate::Unlinked => icon.is_symlink && icon.symlink_target_index.is_none(),
+            Predicate::Context(context) => {
+                let tags: Vec<_> = icon.tags.iter().map(|tag| tag.to_lowercase()).collect();
+
+                if context == "unknown" {
+                    tags.iter()
+                        .all(|tag| !CATEGORIES.iter().any(|c| tag.starts_with(c.1)))
+                } else {
+                    tags.iter()
+                        .enumerate()
+                        .any(|(index, tag)| tag.starts_with(context.as_str()) && index != 0)
+                }
+            }
+            Predicate::Size { .. } | Predicate::SizeDirectory(_) => icon
+                .all_size_directories()
+                .into_iter()
+                .any(|directory| self.matches_size_directory(directory)),
+            Predicate::Format(format) => icon_format(icon).to_lowercase() == *format,
+            Predicate::Theme(theme) => icon
+                .tags
+                .first()
+                .is_some_and(|tag| tag.to_lowercase() == *theme),
+            Predicate::Tag(tag) => icon.tags.iter().any(|t| t.to_lowercase() == *tag),
+            Predicate::Name(pattern) => pattern.matches(&icon.name),
+        }
+    }
+
+    /// Whether a size predicate matches the size directory, [None] being outside of one.
+    pub fn matches_size_directory(&self, directory: Option<SizeDirectory>) -> bool {
+        match (self, directory) {
+            (
+                Predicate::Size { size, scale },
+                Some(SizeDirectory::Fixed {
+                    size: dir_size,
+                    scale: dir_scale,
+                }),
+            ) => dir_size == *size && scale.is_none_or(|scale| scale == dir_scale),
+            (Predicate::SizeDirectory(expected), directory) => directory == *expected,
+            _ => false,
+        }
+    }
+}
+
+/// A single `key:value` term of a query, optionally negated with a leading `-`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryTerm {
+    pub key: String,
+    pub value: String,
+    pub negated: bool,
+    pub predicate: Predicate,
+}
+
+impl QueryTerm {
+    pub fn into_predicate(self) -> Predicate {
+        if self.negated {
+            Predicate::Not(Box::new(self.predicate))
+        } else {
+            self.predicate
+        }
+    }
+}
+
+/// A parsed search query.
+///
+/// Queries consist of free text, which is matched against the icon name, and terms like
+/// `ctx:apps size:16 format:png symbolic:yes theme:Adwaita alias:true -tag:hicolor name:/^edit-/`.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Query {
+    pub text: String,
+    pub terms: Vec<QueryTerm>,
+}
+
+impl Query {
+    pub fn parse(input: &str) -> Self {
+        let mut text = Vec::new();
+        let mut terms = Vec::new();
+
+        for token in tokenize(input) {
+            match parse_term(&token) {
+                Some(term) => terms.push(term),
+                None => text.push(token),
+            }
+        }
+
+        Query {
+            text: text.join(" "),
+            terms,
+        }
+    }
+
+    /// Returns the terms using one of the given keys.
+    pub fn terms_for<'a>(&'a self, keys: &[&str]) -> impl Iterator<Item = &'a QueryTerm> {
+        self.terms
+            .iter()
+            .filter(move |term| keys.contains(&term.key.as_str()))
+    }
+
+    pub fn predicates(&self) -> impl Iterator<Item = Predicate> + '_ {
+        self.terms.iter().cloned().map(QueryTerm::into_predicate)
+    }
+
+    pub fn matches(&self, icon: &IconData) -> bool {
+        self.predicates().all(|predicate| predicate.matches(icon))
+    }
+}
+
+/// Replaces every term with one of the given keys in the query text with the new terms.
+///
+/// The rest of the query is kept as it was typed.
+pub fn replace_terms(input: &str, keys: &[&str], replacement: &[String]) -> String {
+    let mut tokens: Vec<_> = tokenize(input)
+        .into_iter()
+        .filter(|token| parse_term(token).is_none_or(|term| !keys.contains(&term.key.as_str())))
+        .map(|token| {
+            if token.contains(char::is_whitespace) {
+                format!("\"{token}\"")
+            } else {
+                token
+            }
+        })
+        .collect();
+
+    tokens.extend(replacement.iter().cloned());
+    tokens.join(" ")
+}
+
+/// Splits the input on whitespace, keeping quoted parts together.
+fn tokenize(input: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+
+    for c in input.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+
+    tokens
+}
+
+fn parse_term(token: &str) -> Option<QueryTerm> {
+    let (negated, term) = match token.strip_prefix('-') {
+        Some(term) => (true, term),
+        None => (false, token),
+    };
+
+    let (key, value) = term.split_once(':')?;
+    let key = key.to_lowercase();
+    let key = match key.as_str() {
+        "context" => "ctx",
+        "dup" => "duplicate",
+        "fav" => "favorite",
+        key => key,
+    };
+
+    let predicate = Predicate::parse(key, value)?;
+
+    Some(QueryTerm {
+        key: key.to_string(),
+        value: value.to_string(),
+        negated,
+        predicate,
+    })
+}
+
+fn parse_bool(value: &str) -> bool {
+    !matches!(
+        value.to_lowercase().as_str(),
+        "no" | "false" | "0" | "n" | "off"
+    )
+}
+
+fn parse_size(value: &str) -> Option<Predicate> {
+    if let Ok(size) = value.parse() {
+        return Some(Predicate::Size { size, scale: None });
+    }
+
+    if let Some((size, scale)) = value.split_once('@')
+        && let (Ok(size), Ok(scale)) = (size.parse(), scale.trim_end_matches('x').parse())
+    {
+        return Some(Predicate::Size {
+            size,
+            scale: Some(scale),
+        });
+    }
+
+    match value.to_lowercase().as_str() {
+        "none" => Some(Predicate::SizeDirectory(None)),
+        value => SizeDirectory::parse(value).map(|d| Predicate::SizeDirectory(Some(d))),
+    }
+}
+
+/// Resolves a context given by value (`apps`) or name (`Applications`) to its value.
+pub fn context_value(value: &str) -> String {
+    let value = value.to_lowercase();
+
+    CATEGORIES
+        .iter()
+        .find(|(name, v)| name.to_lowercase() == value || *v == value)
+        .map(|(_, v)| v.to_string())
+        .unwrap_or(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_query() {
+        let query = Query::parse("ctx:apps edit size:16 -tag:hicolor \"two words\"");
+
+        assert_eq!(query.text, "edit two words");
+        assert_eq!(
+            query
+                .terms
+                .iter()
+                .map(|t| (t.key.as_str(), t.value.as_str(), t.negated))
+                .collect::<Vec<_>>(),
+            vec![
+                ("ctx", "apps", false),
+                ("size", "16", false),
+                ("tag", "hicolor", true)
+            ]
+        );
+
+        assert_eq!(Query::parse("unknown:key").text, "unknown:key");
+    }
+
+    #[test]
+    fn test_query_matches() {
+        let edit = IconData::at_path(
+            "edit-copy",
+            "/usr/share/icons/Adwaita/16x16/actions/edit-copy.png",
+        );
+        let firefox = IconData {
+            is_symbolic: true,
+            aliases: vec![String::from("web-browser")],
+            ..IconData::at_path(
+                "firefox",
+                "/usr/share/icons/hicolor/scalable/apps/firefox.svg",
+            )
+        };
+
+        for (input, expected) in [
+            ("ctx:actions", (true, false)),
+            ("ctx:Applications", (false, true)),
+            ("size:16", (true, false)),
+            ("size:scalable", (false, true)),
+            ("format:png", (true, false)),
+            ("-format:svg", (true, false)),
+            ("symbolic:yes", (false, true)),
+            ("symbolic:no", (true, false)),
+            ("symbolic:any", (true, true)),
+            ("theme:adwaita", (true, false)),
+            ("alias:true", (false, true)),
+            ("-tag:hicolor", (true, false)),
+            ("name:/^edit-/", (true, false)),
+            ("name:fox", (false, true)),
+        ] {
+            let query = Query::parse(input);
+            assert_eq!(
+                (query.matches(&edit), query.matches(&firefox)),
+                expected,
+                "{input}"
+            );
+        }
+
+        let scanned = IconData {
+            size_directories: BTreeSet::from([
+                Some(SizeDirectory::Fixed { size: 16, scale: 2 }),
+                Some(SizeDirectory::Scalable),
+            ]),
+            ..firefox
+        };
+        assert!(Query::parse("size:16").matches(&scanned));
+        assert!(Query::parse("size:16x16@2").matches(&scanned));
+        assert!(!Query::parse("size:16@1").matches(&scanned));
+    }
+
+    #[test]
+    fn test_replace_terms() {
+        assert_eq!(
+            replace_terms(
+                "edit symbolic:yes ctx:apps",
+                &["symbolic"],
+                &[String::from("symbolic:no")]
+            ),
+            "edit ctx:apps symbolic:no"
+        );
+        assert_eq!(
+            replace_terms("-symlink:yes edit", &["symlink"], &[]),
+            "edit"
+        );
+    }
+
+    #[test]
+    fn test_implies() {
+        let contexts = |contexts: &[&str]| {
+            Predicate::Any(
+                contexts
+                    .iter()
+                    .map(|context| Predicate::Context(context.to_string()))
+                    .collect(),
+            )
+        };
+
+        assert!(contexts(&["apps"]).implies(&contexts(&["apps", "places"])));
+        assert!(!contexts(&["apps", "places"]).implies(&contexts(&["apps"])));
+        assert!(Predicate::Symbolic.implies(&Predicate::Symbolic));
+        assert!(Predicate::Symbolic.implies(&Predicate::Any(vec![Predicate::Symbolic])));
+        assert!(!Predicate::Symbolic.implies(&Predicate::Not(Box::new(Predicate::Symbolic))));
+    }
+
+    #[test]
+    fn test_contains() {
+        let predicate = Predicate::parse("alias", "any").unwrap();
+
+        assert!(predicate.contains(&Predicate::HasAliases));
+        assert!(!predicate.contains(&Predicate::Unlinked));
+        assert!(Predicate::Unlinked.contains(&Predicate::Unlinked));
+    }
+}
@@ -0,0 +1,124 @@
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+
+use super::{FilterMode, SearchMode, TagFilter, load_config, save_config};
+
+const SAVED_SEARCHES_FILE: &str = "saved_searches.toml";
+
+/// The search text and filters of an [super::IconSelector].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchState {
+    #[serde(default)]
+    pub text: String,
+    #[serde(default)]
+    pub search_mode: SearchMode,
+    pub symlink: FilterMode,
+    pub symbolic: FilterMode,
+    pub embedded: FilterMode,
+    pub duplicate: FilterMode,
+    #[serde(default = "either")]
+    pub favorite: FilterMode,
+    #[serde(default)]
+    pub display_invalid_symlinks: bool,
+    pub categories: Vec<String>,
+    /// Included formats, [None] if none are filtered out.
+    pub formats: Option<Vec<String>>,
+    /// Included size directories, [None] if none are filtered out.
+    ///
+    /// Themes differ in their size directories, so a search that doesn't filter them
+    /// keeps showing every one of them.
+    pub size_directories: Option<Vec<String>>,
+    #[serde(default)]
+    pub tags: TagFilter,
+}
+
+/// The default of filters added after searches were first saved.
+fn either() -> FilterMode {
+    FilterMode::Either
+}
+
+/// A named [SearchState], restored from the sidebar.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedSearch {
+    pub name: String,
+    #[serde(flatten)]
+    pub state: SearchState,
+}
+
+/// The saved searches, stored as TOML in the config directory.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedSearches {
+    #[serde(default, rename = "search")]
+    pub searches: Vec<SavedSearch>,
+}
+
+impl SavedSearches {
+    /// Loads the saved searches, empty if none were saved yet.
+    pub fn load() -> Result<Self> {
+        Ok(load_config(SAVED_SEARCHES_FILE)?.unwrap_or_default())
+    }
+
+    pub fn parse(source: &str) -> Result<Self> {
+        Ok(toml::from_str(source)?)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        save_config(SAVED_SEARCHES_FILE, self)
+    }
+
+    /// Adds the search, replacing a saved search with the same name.
+    pub fn insert(&mut self, search: SavedSearch) {
+        match self.searches.iter_mut().find(|s| s.name == search.name) {
+            Some(existing) => *existing = search,
+            None => self.searches.push(search),
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        self.searches.retain(|search| search.name != name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let mut searches = SavedSearches::default();
+        searches.insert(SavedSearch {
+            name: String::from("Symbolic apps"),
+            state: SearchState {
+                text: String::from("ctx:apps"),
+                search_mode: SearchMode::Prefix,
+                symlink: FilterMode::Not,
+                symbolic: FilterMode::Is,
+                embedded: FilterMode::Either,
+                duplicate: FilterMode::Either,
+                favorite: FilterMode::Is,
+                display_invalid_symlinks: false,
+                categories: vec![String::from("apps")],
+                formats: Some(vec![String::from("svg")]),
+                size_directories: None,
+                tags: TagFilter {
+                    excluded: vec![String::from("hicolor")],
+                    ..Default::default()
+                },
+            },
+        });
+
+        let source = toml::to_string_pretty(&searches).unwrap();
+        assert!(source.contains("[[search]]"));
+        assert!(source.contains("symbolic = \"is\""));
+        assert_eq!(SavedSearches::parse(&source).unwrap(), searches);
+
+        let mut renamed = searches.searches[0].clone();
+        renamed.state.text = String::new();
+        searches.insert(renamed);
+        assert_eq!(searches.searches.len(), 1);
+        assert_eq!(searches.searches[0].state.text, "");
+
+        searches.remove("Symbolic apps");
+        assert!(searches.searches.is_empty());
+    }
+}
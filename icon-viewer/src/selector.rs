@@ -1,38 +1,103 @@
 use gtk::glib;
+use gtk::prelude::*;
 use gtk::glib::subclass::prelude::*;
 
 use super::{
-    CATEGORIES, FilterMode, FilterWidget,
-    icon::{IconObject, IconWidget},
-    icon_theme,
+    CATEGORIES, FORMATS, Favorites, FilterMode, FilterWidget, KeywordDatabase, Predicate, Query,
+    QueryTerm, SearchMode, SearchState, SortOrder, TagFilter, TagMatchMode, TextMatcher,
+    icon::{
+        BatchExport, IconData, IconObject, IconWidget, SizeDirectory, load_desktop_entries,
+        load_mime_types, nearest_hashes, read_svg_metadata, scan_size_directories,
+        theme_directory, write_png,
+    },
+    icon_theme, replace_terms,
 };
 
 const DEFAULT_ICON_SIZE: u32 = 64;
 
+/// How long the outcome of an export stays in the status bar.
+const EXPORT_RESULT_SECONDS: u32 = 5;
+
+/// Query keys that are backed by a filter mode of the [FilterWidget].
+const FILTER_MODE_KEYS: &[(&str, &str)] = &[
+    ("symlink", "symlink-filter-mode"),
+    ("symbolic", "symbolic-filter-mode"),
+    ("embedded", "embedded-filter-mode"),
+    ("duplicate", "duplicate-filter-mode"),
+    ("favorite", "favorite-filter-mode"),
+];
+
+/// Query keys that are backed by a list of included values of the [FilterWidget].
+const FILTER_VALUE_KEYS: &[(&str, &str)] = &[
+    ("ctx", "included-categories"),
+    ("format", "included-formats"),
+    ("size", "included-size-directories"),
+];
+
 mod imp {
     use std::{
         cell::{Cell, RefCell},
+        collections::{BTreeSet, HashMap, HashSet},
         rc::Rc,
     };
 
     use gtk::{
-        Allocation, CompositeTemplate, ListItem, SignalListItemFactory, SingleSelection,
-        TemplateChild,
+        Allocation, BitsetIter, CompositeTemplate, ListItem, MultiSelection,
+        SignalListItemFactory, TemplateChild,
         gio::{self, ListStore},
         glib::{Properties, subclass::InitializingObject},
         prelude::*,
         subclass::prelude::*,
     };
 
-    use fuzzy_matcher::{FuzzyMatcher, skim::SkimMatcherV2};
-
-    use crate::icon::IconData;
+    use crate::icon::{
+        HASH_RENDER_SIZE, Pixels, content_key, group_duplicates, perceptual_hash, same_content,
+    };
 
     use super::*;
 
-    type FilterFunction = Box<dyn Fn(&IconObject, &super::IconSelector) -> bool + 'static>;
     type SymlinkMessage = (usize, usize, Vec<(usize, String)>);
 
+    /// Everything the filter matches the icons against, kept to tell how a change affects
+    /// the matches.
+    #[derive(Default)]
+    struct FilterState {
+        search_mode: SearchMode,
+        text: String,
+        include_tags: bool,
+        include_metadata: bool,
+        predicates: Vec<Predicate>,
+    }
+
+    impl FilterState {
+        /// Whether every icon matching this state also matches the other one.
+        fn narrows(&self, other: &FilterState) -> bool {
+            let text = other.text.is_empty()
+                || self.search_mode == other.search_mode
+                    && self.search_mode.narrows(&self.text, &other.text);
+
+            text && (other.include_tags || !self.include_tags)
+                && (other.include_metadata || !self.include_metadata)
+                && other
+                    .predicates
+                    .iter()
+                    .all(|predicate| self.predicates.iter().any(|own| own.implies(predicate)))
+        }
+
+        /// The most precise change from the previous state, [None] if nothing changed.
+        ///
+        /// Telling GTK how the matches changed lets it only recheck the icons that matched
+        /// before, or the ones that didn't.
+        fn change_from(&self, previous: &FilterState) -> Option<gtk::FilterChange> {
+            match (self.narrows(previous), previous.narrows(self)) {
+                (true, true) => None,
+                (true, false) => Some(gtk::FilterChange::MoreStrict),
+                (false, true) => Some(gtk::FilterChange::LessStrict),
+                (false, false) => Some(gtk::FilterChange::Different),
+            }
+        }
+    }
+
     #[derive(CompositeTemplate, Properties, Default)]
     #[properties(wrapper_type = super::IconSelector)]
     #[template(resource = "/codes/blaine/NettIconViewer/icon_selector.ui")]
@@ -55,19 +120,56 @@ mod imp {
         #[template_child]
         pub search: TemplateChild<gtk::SearchEntry>,
 
+        #[template_child]
+        search_mode_dropdown: TemplateChild<gtk::DropDown>,
+
+        #[template_child]
+        sort_order_dropdown: TemplateChild<gtk::DropDown>,
+
+        #[template_child]
+        relevance_toggle: TemplateChild<gtk::ToggleButton>,
+
+        #[template_child]
+        metadata_toggle: TemplateChild<gtk::ToggleButton>,
+
+        #[template_child]
+        tag_dropdown: TemplateChild<gtk::DropDown>,
+
+        #[template_child]
+        tag_mode_toggle: TemplateChild<gtk::ToggleButton>,
+
+        #[template_child]
+        tag_chips: TemplateChild<gtk::FlowBox>,
+
         #[template_child]
         pub filter_widget: TemplateChild<FilterWidget>,
 
         #[template_child]
         pub status_revealer: TemplateChild<gtk::Revealer>,
 
+        #[template_child]
+        loading_status: TemplateChild<gtk::Box>,
+
         #[template_child]
         progress: TemplateChild<gtk::ProgressBar>,
 
+        #[template_child]
+        export_status: TemplateChild<gtk::Box>,
+
+        #[template_child]
+        export_label: TemplateChild<gtk::Label>,
+
+        #[template_child]
+        export_progress: TemplateChild<gtk::ProgressBar>,
+
+        #[template_child]
+        export_cancel_button: TemplateChild<gtk::Button>,
+
         #[property(get, set = set_icon_size, construct, default = DEFAULT_ICON_SIZE)]
         pub icon_size: Cell<u32>,
 
-        #[property(get, set)]
+        /// The position of the first selected icon, see [IconSelector::selected_icons].
+        #[property(get, set = set_selected)]
         pub selected: Cell<u32>,
 
         #[property(get, set)]
@@ -76,12 +178,46 @@ mod imp {
         #[property(get, set = set_include_tags_in_search, construct, default = true)]
         pub include_tags_in_search: Cell<bool>,
 
-        #[property(get, set = set_included_tags, construct)]
-        pub included_tags: RefCell<Vec<String>>,
+        /// Whether the descriptive text of SVG icons is searched, see [IconData::svg_metadata].
+        #[property(get, set = set_include_metadata_in_search, construct, default = false)]
+        pub include_metadata_in_search: Cell<bool>,
+
+        #[property(get, set = set_tag_filter)]
+        pub tag_filter: RefCell<TagFilter>,
+
+        #[property(get, set = set_search_mode, construct, builder(SearchMode::Fuzzy))]
+        pub search_mode: Cell<SearchMode>,
+
+        #[property(get, set = set_sort_order, construct, builder(SortOrder::Name))]
+        pub sort_order: Cell<SortOrder>,
+
+        /// Whether icons matching the search better are sorted first, before the sort order.
+        #[property(get, set = set_sort_by_relevance, construct, default = true)]
+        pub sort_by_relevance: Cell<bool>,
 
         #[property(get)]
         pub num_items: Cell<u32>,
 
+        pub query: RefCell<Query>,
+        pub matcher: RefCell<TextMatcher>,
+        filter_state: RefCell<FilterState>,
+        /// Relevance scores of the icons, [None] if they don't match the current search and
+        /// filters.
+        ///
+        /// Filled in as the filter reaches the icons and read by the sorter, cleared on every
+        /// change.
+        scores: RefCell<HashMap<IconObject, Option<i64>>>,
+        syncing_query: Cell<bool>,
+
+        favorites: RefCell<Favorites>,
+        /// Whether the favorites failed to load, in which case saving would overwrite them.
+        favorites_unreadable: Cell<bool>,
+        /// Cancels the running batch export, see [IconSelector::export_icons].
+        export_cancellable: RefCell<Option<gio::Cancellable>>,
+        /// Counts the exports and results shown in the status bar, so an outdated result
+        /// doesn't hide a newer one.
+        export_serial: Cell<u32>,
+
         displayed_icons: Rc<RefCell<Vec<IconWidget>>>,
 
         #[property(get, nullable)]
@@ -91,6 +227,18 @@ mod imp {
         list: gtk::SortListModel,
     }
 
+    fn set_selected(imp: &IconSelector, position: u32) {
+        let Some(model) = imp.view.model() else {
+            return;
+        };
+
+        if position == gtk::INVALID_LIST_POSITION {
+            model.unselect_all();
+        } else {
+            model.select_item(position, true);
+        }
+    }
+
     fn set_icon_size(imp: &IconSelector, value: u32) {
         imp.icon_size.set(value);
 
@@ -107,10 +255,41 @@ mod imp {
         imp.obj().notify_include_tags_in_search();
     }
 
-    fn set_included_tags(imp: &IconSelector, value: Vec<String>) {
-        *imp.included_tags.borrow_mut() = value;
+    fn set_include_metadata_in_search(imp: &IconSelector, value: bool) {
+        imp.include_metadata_in_search.set(value);
+        imp.filter_changed();
+        imp.obj().notify_include_metadata_in_search();
+    }
+
+    fn set_search_mode(imp: &IconSelector, value: SearchMode) {
+        imp.search_mode.set(value);
+        imp.update_matcher();
         imp.filter_changed();
-        imp.obj().notify_included_tags();
+        imp.sorter.changed(gtk::SorterChange::Different);
+        imp.obj().notify_search_mode();
+    }
+
+    fn set_sort_order(imp: &IconSelector, value: SortOrder) {
+        imp.sort_order.set(value);
+        imp.sorter.changed(gtk::SorterChange::Different);
+        imp.obj().notify_sort_order();
+    }
+
+    fn set_sort_by_relevance(imp: &IconSelector, value: bool) {
+        imp.sort_by_relevance.set(value);
+        imp.sorter.changed(gtk::SorterChange::Different);
+        imp.obj().notify_sort_by_relevance();
+    }
+
+    fn set_tag_filter(imp: &IconSelector, value: TagFilter) {
+        if *imp.tag_filter.borrow() == value {
+            return;
+        }
+
+        imp.tag_filter.replace(value);
+        imp.update_tag_chips();
+        imp.filter_changed();
+        imp.obj().notify_tag_filter();
     }
 
     #[glib::object_subclass]
@@ -136,6 +315,147 @@ mod imp {
             self.list.item(self.selected.get()).and_downcast()
         }
 
+        pub fn selected_icons(&self) -> Vec<IconObject> {
+            let Some(selection) = self.view.model().map(|model| model.selection()) else {
+                return Vec::new();
+            };
+            let Some((iter, first)) = BitsetIter::init_first(&selection) else {
+                return Vec::new();
+            };
+
+            std::iter::once(first)
+                .chain(iter)
+                .filter_map(|position| self.list.item(position).and_downcast())
+                .collect()
+        }
+
+        pub fn filtered_icons(&self) -> Vec<IconObject> {
+            self.list
+                .iter::<IconObject>()
+                .filter_map(Result::ok)
+                .collect()
+        }
+
+        /// Keeps [Self::selected] at the first selected icon as the selection and list change.
+        fn update_selected(&self) {
+            let position = self
+                .view
+                .model()
+                .map(|model| model.selection().minimum())
+                .unwrap_or(gtk::INVALID_LIST_POSITION);
+
+            if self.selected.replace(position) != position {
+                self.obj().notify_selected();
+            }
+        }
+
+        /// Renders the icons into the export's directory one at a time, so the interface
+        /// stays responsive, showing the progress in the status bar.
+        pub fn export_icons(&self, names: Vec<String>, export: BatchExport) {
+            let Some(renderer) = self.obj().native().and_then(|native| native.renderer()) else {
+                log::warn!("Cannot export icons before the selector is shown");
+                return;
+            };
+
+            let cancellable = gio::Cancellable::new();
+            if let Some(previous) = self.export_cancellable.replace(Some(cancellable.clone())) {
+                previous.cancel();
+            }
+
+            self.export_serial.set(self.export_serial.get() + 1);
+            self.export_label.set_label("Exporting Icons");
+            self.export_progress.set_fraction(0.0);
+            self.export_progress.set_visible(true);
+            self.export_cancel_button.set_visible(true);
+            self.export_status.set_visible(true);
+            self.update_status();
+
+            let obj = self.obj().clone();
+            glib::spawn_future_local(async move {
+                let imp = obj.imp();
+                let jobs: Vec<_> = export.jobs(&names).collect();
+                let mut failed = 0;
+
+                for (index, (name, size)) in jobs.iter().copied().enumerate() {
+                    if cancellable.is_cancelled() {
+                        log::debug!("Cancelled export after {} of {} icons", index, jobs.len());
+                        break;
+                    }
+
+                    let path = export.path(name, size);
+                    let result = match export.render(name, size, &renderer) {
+                        Ok(bytes) => gio::spawn_blocking(move || write_png(&path, &bytes))
+                            .await
+                            .expect("Failed to write PNG"),
+                        Err(err) => Err(err),
+                    };
+
+                    if let Err(err) = result {
+                        log::warn!("Failed to export \"{}\" at {}px: {}", name, size, err);
+                        failed += 1;
+                    }
+
+                    imp.export_progress
+                        .set_fraction((index + 1) as f64 / jobs.len() as f64);
+                }
+
+                let result = if failed > 0 {
+                    log::warn!("Failed to export {} of {} icons", failed, jobs.len());
+                    format!("Failed to export {} of {} icons", failed, jobs.len())
+                } else if cancellable.is_cancelled() {
+                    String::from("Export cancelled")
+                } else {
+                    format!("Exported {} icons", jobs.len())
+                };
+
+                // A newer export has taken over the status bar
+                if imp.export_cancellable.borrow().as_ref() != Some(&cancellable) {
+                    return;
+                }
+
+                imp.export_cancellable.replace(None);
+                imp.show_export_result(&result);
+            });
+        }
+
+        /// Shows the outcome of an export in the status bar for a few seconds.
+        pub fn show_export_result(&self, message: &str) {
+            let serial = self.export_serial.get() + 1;
+            self.export_serial.set(serial);
+
+            self.export_label.set_label(message);
+            self.export_progress.set_visible(false);
+            self.export_cancel_button.set_visible(false);
+            self.export_status.set_visible(true);
+            self.update_status();
+
+            let obj = self.obj().clone();
+            glib::spawn_future_local(async move {
+                glib::timeout_future_seconds(EXPORT_RESULT_SECONDS).await;
+
+                let imp = obj.imp();
+                if imp.export_serial.get() == serial {
+                    imp.export_status.set_visible(false);
+                    imp.update_status();
+                }
+            });
+        }
+
+        /// Reveals the status bar while icons are loaded or exported, each with their own
+        /// progress.
+        fn update_status(&self) {
+            self.status_revealer.set_reveal_child(
+                self.loading_status.is_visible() || self.export_status.is_visible(),
+            );
+        }
+
+        #[template_callback]
+        fn cancel_export(&self) {
+            if let Some(cancellable) = self.export_cancellable.borrow().as_ref() {
+                cancellable.cancel();
+            }
+        }
+
         fn icons(&self) -> ListStore {
             self.icons.borrow().clone().expect("Icons not set")
         }
@@ -158,13 +478,423 @@ mod imp {
         }
 
         #[template_callback]
-        fn filter_changed(&self) {
+        pub fn filter_changed(&self) {
+            // The initial state is taken once the icons are loaded
+            if self.icons.borrow().is_none() {
+                return;
+            }
+
+            let state = self.filter_state();
+            let Some(change) = state.change_from(&self.filter_state.borrow()) else {
+                return;
+            };
+
+            self.filter_state.replace(state);
+            self.scores.borrow_mut().clear();
+            self.filter.changed(change);
+        }
+
+        /// Rematches every icon after data they are matched against was loaded.
+        fn refilter(&self) {
+            self.scores.borrow_mut().clear();
             self.filter.changed(gtk::FilterChange::Different);
+            self.sorter.changed(gtk::SorterChange::Different);
         }
 
         #[template_callback]
         fn search_changed(&self) {
+            let query = Query::parse(&self.search.text());
+            self.apply_query_to_filters(&query);
+            self.query.replace(query);
+            self.update_matcher();
+
             self.filter_changed();
+            self.sorter.changed(gtk::SorterChange::Different);
+        }
+
+        /// Stores the favorite state of the icon, refiltering if favorites are filtered.
+        fn favorite_toggled(&self, icon: &IconObject) {
+            let mut favorites = self.favorites.borrow_mut();
+            if !favorites.set(&icon.name(), icon.is_favorite()) {
+                return;
+            }
+
+            if self.favorites_unreadable.get() {
+                log::warn!("Not saving favorites, the favorites file couldn't be loaded");
+            } else if let Err(err) = favorites.save() {
+                log::warn!("Failed to save favorites: {}", err);
+            }
+            drop(favorites);
+
+            if self.filter_widget.favorite_filter_mode() != FilterMode::Either {
+                self.refilter();
+            }
+        }
+
+        /// Rematches the icons after their search terms were loaded, see
+        /// [IconData::search_terms].
+        fn search_terms_changed(&self) {
+            if !self.matcher.borrow().is_empty() {
+                self.refilter();
+            }
+        }
+
+        /// Rebuilds the matcher for the free text of the query.
+        fn update_matcher(&self) {
+            let matcher = TextMatcher::new(self.search_mode.get(), &self.query.borrow().text);
+            self.matcher.replace(matcher);
+        }
+
+        /// Collects the current search and filters.
+        fn filter_state(&self) -> FilterState {
+            FilterState {
+                search_mode: self.search_mode.get(),
+                text: self.query.borrow().text.clone(),
+                include_tags: self.include_tags_in_search.get(),
+                include_metadata: self.include_metadata_in_search.get(),
+                predicates: self.predicates(),
+            }
+        }
+
+        /// Scores the icon against the current search and filters, [None] if it doesn't
+        /// match.
+        ///
+        /// Scored when the filter reaches the icon, which the incremental filter model
+        /// spreads over several frames.
+        fn score(&self, icon: &IconObject) -> Option<i64> {
+            if let Some(score) = self.scores.borrow().get(icon) {
+                return *score;
+            }
+
+            let state = self.filter_state.borrow();
+            let matcher = self.matcher.borrow();
+            let data = icon.data();
+            let score = icon_score(&matcher, &data);
+
+            let matches = score.is_some()
+                || state.include_tags && matcher.score(&data.tags.join(" ")).is_some()
+                || state.include_metadata
+                    && matcher
+                        .best_match(data.svg_metadata.iter().map(String::as_str))
+                        .is_some();
+            let score = (matches && state.predicates.iter().all(|p| p.matches(&data)))
+                .then(|| score.unwrap_or(0));
+
+            self.scores.borrow_mut().insert(icon.clone(), score);
+            score
+        }
+
+        #[template_callback]
+        fn filters_changed(&self) {
+            if !self.syncing_query.get() {
+                self.apply_filters_to_query();
+            }
+
+            self.filter_changed();
+        }
+
+        /// Updates the [FilterWidget] to match the terms of the query that it has toggles for.
+        ///
+        /// Toggles without a term in the query are reset to their default, so removing a term
+        /// from the search text doesn't leave its filter active.
+        fn apply_query_to_filters(&self, query: &Query) {
+            let filter_widget = self.filter_widget.get();
+            self.syncing_query.set(true);
+
+            for (key, property) in FILTER_MODE_KEYS {
+                let mode = match query.terms_for(&[*key]).last() {
+                    None => self.default_filter_mode(property),
+                    Some(term) if matches!(term.predicate, Predicate::Any(_)) => FilterMode::Either,
+                    Some(term) if matches!(term.predicate, Predicate::Not(_)) == term.negated => {
+                        FilterMode::Is
+                    }
+                    Some(_) => FilterMode::Not,
+                };
+
+                if filter_widget.property::<FilterMode>(property) != mode {
+                    filter_widget.set_property(property, mode);
+                }
+            }
+
+            for (key, property) in FILTER_VALUE_KEYS {
+                let (excluded, included): (Vec<_>, Vec<_>) =
+                    query.terms_for(&[*key]).partition(|term| term.negated);
+
+                let mut values: Vec<_> = self
+                    .filter_values(key)
+                    .into_iter()
+                    .filter(|value| {
+                        (included.is_empty() || included.iter().any(|t| term_selects(t, value)))
+                            && !excluded.iter().any(|t| term_selects(t, value))
+                    })
+                    .collect();
+
+                let mut current = filter_widget.property::<Vec<String>>(property);
+                current.sort();
+                values.sort();
+
+                if current != values {
+                    filter_widget.set_property(property, values);
+                }
+            }
+
+            self.syncing_query.set(false);
+        }
+
+        /// Rewrites the terms of the query that the [FilterWidget] has toggles for, so the
+        /// search text always shows the active filters.
+        fn apply_filters_to_query(&self) {
+            let filter_widget = self.filter_widget.get();
+            let current = self.search.text().to_string();
+            let query = Query::parse(&current);
+            let mut text = current.clone();
+
+            for (key, property) in FILTER_MODE_KEYS {
+                let mode = filter_widget.property::<FilterMode>(property);
+                let replacement = if mode == self.default_filter_mode(property) {
+                    vec![]
+                } else {
+                    let value = match mode {
+                        FilterMode::Is => "yes",
+                        FilterMode::Not => "no",
+                        FilterMode::Either => "any",
+                    };
+                    vec![format!("{key}:{value}")]
+                };
+
+                if !replacement.is_empty() || query.terms_for(&[*key]).next().is_some() {
+                    text = replace_terms(&text, &[*key], &replacement);
+                }
+            }
+
+            for (key, property) in FILTER_VALUE_KEYS {
+                let mut values = filter_widget.property::<Vec<String>>(property);
+                values.sort();
+
+                let replacement: Vec<_> = if values.len() == self.filter_values(key).len() {
+                    vec![]
+                } else {
+                    values
+                        .iter()
+                        .map(|value| format!("{key}:{value}"))
+                        .collect()
+                };
+
+                if !replacement.is_empty() || query.terms_for(&[*key]).next().is_some() {
+                    text = replace_terms(&text, &[*key], &replacement);
+                }
+            }
+
+            if text != current {
+                self.syncing_query.set(true);
+                self.search.set_text(&text);
+                self.syncing_query.set(false);
+            }
+        }
+
+        /// The mode of a [FilterWidget] toggle without a term in the query.
+        fn default_filter_mode(&self, property: &str) -> FilterMode {
+            self.filter_widget
+                .find_property(property)
+                .and_then(|pspec| pspec.default_value().get().ok())
+                .unwrap_or(FilterMode::Either)
+        }
+
+        /// Lists the size directories of the icons in the [FilterWidget], then includes the
+        /// ones the `size:` terms of the search select.
+        fn update_size_directories(&self, icons: &[IconObject]) {
+            let directories: BTreeSet<_> = icons
+                .iter()
+                .flat_map(|icon| icon.data().all_size_directories())
+                .collect();
+
+            self.syncing_query.set(true);
+            self.filter_widget.set_size_directories(
+                directories
+                    .iter()
+                    .flatten()
+                    .map(|directory| (directory.to_string(), directory.to_string()))
+                    .chain(
+                        directories
+                            .contains(&None)
+                            .then(|| (String::from("Other"), String::from("none"))),
+                    )
+                    .collect(),
+            );
+            self.syncing_query.set(false);
+
+            self.apply_query_to_filters(&Query::parse(&self.search.text()));
+        }
+
+        /// Every value the [FilterWidget] list of the key can include, see [FILTER_VALUE_KEYS].
+        fn filter_values(&self, key: &str) -> Vec<String> {
+            match key {
+                "ctx" => all_categories(),
+                "format" => FORMATS.iter().map(|(_, value)| value.to_string()).collect(),
+                _ => self.filter_widget.size_directories(),
+            }
+        }
+
+        /// Adds the tag picked in the tag dropdown to the filter.
+        fn tag_selected(&self) {
+            let Some(tag) = self
+                .tag_dropdown
+                .selected_item()
+                .and_downcast::<gtk::StringObject>()
+            else {
+                return;
+            };
+
+            self.tag_dropdown.set_selected(gtk::INVALID_LIST_POSITION);
+            self.obj().update_tag_filter(|filter| filter.add(&tag.string()));
+        }
+
+        #[template_callback]
+        fn tag_mode_toggled(&self) {
+            let mode = if self.tag_mode_toggle.is_active() {
+                TagMatchMode::Any
+            } else {
+                TagMatchMode::All
+            };
+
+            self.obj().update_tag_filter(|filter| filter.mode = mode);
+        }
+
+        /// Recreates the chips of the tag bar from the tag filter.
+        fn update_tag_chips(&self) {
+            let filter = self.tag_filter.borrow().clone();
+            self.tag_chips.remove_all();
+
+            self.tag_mode_toggle.set_active(filter.mode == TagMatchMode::Any);
+            self.tag_mode_toggle.set_visible(filter.included.len() > 1);
+
+            for tag in filter.included.iter().chain(&filter.excluded) {
+                let excluded = filter.is_excluded(tag);
+
+                let toggle = gtk::Button::builder()
+                    .label(if excluded {
+                        format!("-{tag}")
+                    } else {
+                        tag.clone()
+                    })
+                    .tooltip_text(if excluded {
+                        "Excluded, click to include"
+                    } else {
+                        "Included, click to exclude"
+                    })
+                    .build();
+                if excluded {
+                    toggle.add_css_class("error");
+                }
+
+                let remove = gtk::Button::builder()
+                    .icon_name("window-close-symbolic")
+                    .tooltip_text("Remove Tag")
+                    .build();
+
+                let obj = self.obj().clone();
+                let toggled_tag = tag.clone();
+                toggle.connect_clicked(move |_| {
+                    obj.update_tag_filter(|filter| filter.toggle(&toggled_tag));
+                });
+
+                let obj = self.obj().clone();
+                let removed_tag = tag.clone();
+                remove.connect_clicked(move |_| {
+                    obj.update_tag_filter(|filter| filter.remove(&removed_tag));
+                });
+
+                let chip = gtk::Box::builder().css_classes(["linked"]).build();
+                chip.append(&toggle);
+                chip.append(&remove);
+
+                self.tag_chips.append(&chip);
+            }
+        }
+
+        /// Collects the predicates of the filters and the search query.
+        pub fn predicates(&self) -> Vec<Predicate> {
+            let filter_widget = self.filter_widget.get();
+            let mut predicates = Vec::new();
+
+            let mode_predicate = |mode: FilterMode, predicate: Predicate| match mode {
+                FilterMode::Is => Some(predicate),
+                FilterMode::Not => Some(Predicate::Not(Box::new(predicate))),
+                FilterMode::Either => None,
+            };
+
+            predicates.extend(match filter_widget.symlink_filter_mode() {
+                FilterMode::Not if filter_widget.display_invalid_symlinks() => {
+                    Some(Predicate::Any(vec![
+                        Predicate::Not(Box::new(Predicate::Symlink)),
+                        Predicate::Unlinked,
+                    ]))
+                }
+                mode => mode_predicate(mode, Predicate::Symlink),
+            });
+
+            predicates.extend(mode_predicate(
+                filter_widget.embedded_filter_mode(),
+                Predicate::Embedded,
+            ));
+            predicates.extend(mode_predicate(
+                filter_widget.symbolic_filter_mode(),
+                Predicate::Symbolic,
+            ));
+            predicates.extend(mode_predicate(
+                filter_widget.duplicate_filter_mode(),
+                Predicate::Duplicate,
+            ));
+            predicates.extend(mode_predicate(
+                filter_widget.favorite_filter_mode(),
+                Predicate::Favorite,
+            ));
+
+            predicates.extend(self.tag_filter.borrow().predicates());
+
+            predicates.push(Predicate::Any(
+                filter_widget
+                    .included_categories()
+                    .into_iter()
+                    .map(Predicate::Context)
+                    .collect(),
+            ));
+
+            let formats = filter_widget.included_formats();
+            if formats.len() != FORMATS.len() {
+                predicates.push(Predicate::Any(
+                    formats.into_iter().map(Predicate::Format).collect(),
+                ));
+            }
+
+            if filter_widget.filters_size_directories() {
+                predicates.push(Predicate::Any(
+                    filter_widget
+                        .included_size_directories()
+                        .iter()
+                        .map(|directory| {
+                            Predicate::SizeDirectory(SizeDirectory::parse(directory))
+                        })
+                        .collect(),
+                ));
+            }
+
+            let query = self.query.borrow();
+            predicates.extend(
+                query
+                    .terms
+                    .iter()
+                    .filter(|term| {
+                        !FILTER_MODE_KEYS
+                            .iter()
+                            .chain(FILTER_VALUE_KEYS)
+                            .any(|(key, _)| term.key == *key)
+                    })
+                    .cloned()
+                    .map(|term| term.into_predicate()),
+            );
+
+            predicates
         }
 
         #[template_callback]
@@ -183,6 +913,33 @@ mod imp {
         }
     }
 
+    /// Scores an icon by its name, or by its best matching search term if that scores
+    /// higher, see [IconData::search_terms].
+    fn icon_score(matcher: &TextMatcher, icon: &IconData) -> Option<i64> {
+        let term_score = matcher.best_match(icon.search_terms()).map(|(_, score)| score);
+
+        matcher.score(&icon.name).max(term_score)
+    }
+
+    /// Whether the term selects a value of a [FilterWidget] list, see [FILTER_VALUE_KEYS].
+    fn term_selects(term: &QueryTerm, value: &str) -> bool {
+        match &term.predicate {
+            // Contexts match tags by prefix, like `ctx:app` matches `apps`
+            Predicate::Context(context) => value.starts_with(context.as_str()),
+            Predicate::Format(format) => format == value,
+            predicate => predicate.matches_size_directory(SizeDirectory::parse(value)),
+        }
+    }
+
+    /// Values of every category the [FilterWidget] can toggle.
+    fn all_categories() -> Vec<String> {
+        CATEGORIES
+            .iter()
+            .map(|(_, value)| value.to_string())
+            .chain([String::from("unknown")])
+            .collect()
+    }
+
     fn handle_filter_pending(obj: &super::IconSelector) {
         let imp = obj.imp();
         imp.scroll.vadjustment().set_value(0.0);
@@ -194,18 +951,142 @@ mod imp {
         fn constructed(&self) {
             self.parent_constructed();
 
+            let modes = gtk::StringList::new(&SearchMode::ALL.map(|mode| mode.name()));
+            self.search_mode_dropdown.set_model(Some(&modes));
+            self.obj()
+                .bind_property("search-mode", &*self.search_mode_dropdown, "selected")
+                .transform_to(|_, mode: SearchMode| {
+                    SearchMode::ALL
+                        .iter()
+                        .position(|m| *m == mode)
+                        .map(|index| index as u32)
+                })
+                .transform_from(|_, index: u32| SearchMode::ALL.get(index as usize).copied())
+                .bidirectional()
+                .sync_create()
+                .build();
+
+            let orders = gtk::StringList::new(&SortOrder::ALL.map(|order| order.name()));
+            self.sort_order_dropdown.set_model(Some(&orders));
+            self.obj()
+                .bind_property("sort-order", &*self.sort_order_dropdown, "selected")
+                .transform_to(|_, order: SortOrder| {
+                    SortOrder::ALL
+                        .iter()
+                        .position(|o| *o == order)
+                        .map(|index| index as u32)
+                })
+                .transform_from(|_, index: u32| SortOrder::ALL.get(index as usize).copied())
+                .bidirectional()
+                .sync_create()
+                .build();
+
+            self.obj()
+                .bind_property("sort-by-relevance", &*self.relevance_toggle, "active")
+                .bidirectional()
+                .sync_create()
+                .build();
+
+            self.obj()
+                .bind_property("include-metadata-in-search", &*self.metadata_toggle, "active")
+                .bidirectional()
+                .sync_create()
+                .build();
+
+            let keywords = KeywordDatabase::load().unwrap_or_else(|err| {
+                log::warn!("Failed to load keywords: {}", err);
+                KeywordDatabase::bundled()
+            });
+
+            match Favorites::load() {
+                Ok(favorites) => {
+                    self.favorites.replace(favorites);
+                }
+                Err(err) => {
+                    log::warn!("Failed to load favorites: {}", err);
+                    self.favorites_unreadable.set(true);
+                }
+            }
+
             let theme = icon_theme();
             let icons = theme
                 .icon_names()
                 .iter()
-                .map(|n| IconObject::new(n, self.icon_size.get()))
+                .map(|n| {
+                    let icon = IconObject::new(n, self.icon_size.get());
+                    icon.set_keywords(keywords.keywords_for(n));
+                    icon.set_is_favorite(self.favorites.borrow().contains(n));
+                    icon
+                })
                 .collect::<Vec<_>>();
 
+            for icon in &icons {
+                let obj = self.obj().clone();
+                icon.connect_is_favorite_notify(move |icon| obj.imp().favorite_toggled(icon));
+            }
+
             let data = icons
                 .iter()
                 .map(|icon| icon.data().clone())
                 .collect::<Vec<_>>();
 
+            let tags: BTreeSet<_> = data.iter().flat_map(|icon| icon.tags.iter()).collect();
+            let tags: Vec<_> = tags.into_iter().map(String::as_str).collect();
+            self.tag_dropdown.set_model(Some(&gtk::StringList::new(&tags)));
+            self.tag_dropdown.set_selected(gtk::INVALID_LIST_POSITION);
+
+            // Connected after setting the model, which selects the first tag
+            let obj = self.obj().clone();
+            self.tag_dropdown
+                .connect_selected_notify(move |_| obj.imp().tag_selected());
+
+            self.update_size_directories(&icons);
+
+            let search_path = theme.search_path();
+            let theme_dirs: BTreeSet<_> = data
+                .iter()
+                .filter_map(|icon| icon.path.as_deref())
+                .filter_map(|path| theme_directory(path, &search_path))
+                .collect();
+
+            let size_directory_icons = icons.clone();
+            let obj = self.obj().clone();
+            glib::spawn_future_local(async move {
+                let scanned = gio::spawn_blocking(move || {
+                    theme_dirs
+                        .into_iter()
+                        .map(|dir| {
+                            let directories = scan_size_directories(&dir);
+                            (dir, directories)
+                        })
+                        .collect::<HashMap<_, _>>()
+                })
+                .await;
+
+                let Ok(mut scanned) = scanned else {
+                    return;
+                };
+
+                for icon in &size_directory_icons {
+                    let theme_dir = icon
+                        .data()
+                        .path
+                        .as_deref()
+                        .and_then(|path| theme_directory(path, &search_path));
+
+                    if let Some(directories) = theme_dir
+                        .and_then(|dir| scanned.get_mut(&dir))
+                        .and_then(|directories| directories.remove(&icon.name()))
+                    {
+                        icon.set_size_directories(directories);
+                    }
+                }
+
+                let imp = obj.imp();
+                imp.update_size_directories(&size_directory_icons);
+                imp.refilter();
+            });
+
             let (symlinks, non_symlinks): (Vec<_>, Vec<_>) = data
                 .iter()
                 .cloned()
@@ -242,15 +1123,199 @@ mod imp {
                 }
             });
 
+            let hash_targets: Vec<_> = data
+                .iter()
+                .enumerate()
+                .filter(|(_, data)| !data.is_symlink)
+                .filter_map(|(index, data)| data.path.clone().map(|path| (index, path)))
+                .collect();
+
+            let render_targets = hash_targets.clone();
+
+            let (duplicate_tx, duplicate_rx) = async_channel::bounded::<Vec<Vec<usize>>>(1);
+            gio::spawn_blocking(move || {
+                let keys: Vec<_> = hash_targets
+                    .iter()
+                    .filter_map(|(index, path)| match content_key(path) {
+                        Ok(key) => Some((*index, key)),
+                        Err(err) => {
+                            log::warn!("Failed to hash \"{}\": {}", path.display(), err);
+                            None
+                        }
+                    })
+                    .collect();
+
+                let paths: HashMap<_, _> = hash_targets
+                    .iter()
+                    .map(|(index, path)| (*index, path.as_path()))
+                    .collect();
+                let groups = group_duplicates(&keys, |a, b| {
+                    same_content(paths[&a], paths[&b]).unwrap_or_else(|err| {
+                        log::warn!("Failed to compare \"{}\": {}", paths[&a].display(), err);
+                        false
+                    })
+                });
+
+                duplicate_tx
+                    .send_blocking(groups)
+                    .expect("Failed to send duplicates");
+            });
+
+            let duplicate_icons = icons.clone();
+            let obj = self.obj().clone();
+            let filter_widget = self.filter_widget.get();
+            glib::spawn_future_local(async move {
+                let Ok(groups) = duplicate_rx.recv().await else {
+                    return;
+                };
+
+                log::debug!("Found {} groups of duplicate icons", groups.len());
+
+                for group in groups {
+                    for index in &group {
+                        let names = group
+                            .iter()
+                            .filter(|i| *i != index)
+                            .map(|i| duplicate_icons[*i].name())
+                            .collect();
+
+                        duplicate_icons[*index].set_duplicates(names);
+                    }
+                }
+
+                if filter_widget.duplicate_filter_mode() != FilterMode::Either {
+                    obj.imp().refilter();
+                }
+            });
+
+            let desktop_icons = icons.clone();
+            let obj = self.obj().clone();
+            glib::spawn_future_local(async move {
+                let Ok(mut entries) = gio::spawn_blocking(load_desktop_entries).await else {
+                    return;
+                };
+
+                log::debug!("Found desktop entries for {} icons", entries.len());
+
+                for icon in &desktop_icons {
+                    if let Some(entries) = entries.remove(&icon.name()) {
+                        icon.set_desktop_entries(entries);
+                    }
+                }
+
+                obj.imp().search_terms_changed();
+            });
+
+            let mime_icons = icons.clone();
+            let icon_names: HashSet<_> = data.iter().map(|icon| icon.name.clone()).collect();
+            let obj = self.obj().clone();
+            glib::spawn_future_local(async move {
+                let mime_types = gio::spawn_blocking(move || load_mime_types(&icon_names)).await;
+                let Ok(mut mime_types) = mime_types else {
+                    return;
+                };
+
+                log::debug!("Found MIME types for {} icons", mime_types.len());
+
+                for icon in &mime_icons {
+                    if let Some(mime_types) = mime_types.remove(&icon.name()) {
+                        icon.set_mime_types(mime_types);
+                    }
+                }
+
+                obj.imp().search_terms_changed();
+            });
+
+            let metadata_targets: Vec<_> = data
+                .iter()
+                .enumerate()
+                .filter_map(|(index, data)| data.path.clone().map(|path| (index, path)))
+                .filter(|(_, path)| path.extension().is_some_and(|ext| ext == "svg"))
+                .collect();
+
+            let metadata_icons = icons.clone();
+            let obj = self.obj().clone();
+            glib::spawn_future_local(async move {
+                let metadata = gio::spawn_blocking(move || {
+                    metadata_targets
+                        .into_iter()
+                        .filter_map(|(index, path)| match read_svg_metadata(&path) {
+                            Ok(metadata) if !metadata.is_empty() => Some((index, metadata)),
+                            Ok(_) => None,
+                            Err(err) => {
+                                log::debug!("Failed to read \"{}\": {}", path.display(), err);
+                                None
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .await;
+
+                let Ok(metadata) = metadata else {
+                    return;
+                };
+
+                log::debug!("Found SVG metadata for {} icons", metadata.len());
+
+                for (index, metadata) in metadata {
+                    metadata_icons[index].set_svg_metadata(metadata);
+                }
+
+                if obj.include_metadata_in_search() {
+                    obj.imp().search_terms_changed();
+                }
+            });
+
+            let (similarity_tx, similarity_rx) = async_channel::bounded::<Vec<(usize, u64)>>(1);
+            gio::spawn_blocking(move || {
+                let hashes: Vec<_> = render_targets
+                    .iter()
+                    .filter_map(|(index, path)| {
+                        let pixbuf = gtk::gdk_pixbuf::Pixbuf::from_file_at_size(
+                            path,
+                            HASH_RENDER_SIZE,
+                            HASH_RENDER_SIZE,
+                        )
+                        .inspect_err(|err| {
+                            log::debug!("Failed to render \"{}\": {}", path.display(), err)
+                        })
+                        .ok()?;
+
+                        let bytes = pixbuf.read_pixel_bytes();
+                        let hash = perceptual_hash(&Pixels {
+                            data: &bytes,
+                            width: pixbuf.width() as usize,
+                            height: pixbuf.height() as usize,
+                            rowstride: pixbuf.rowstride() as usize,
+                            channels: pixbuf.n_channels() as usize,
+                        });
+
+                        Some((*index, hash))
+                    })
+                    .collect();
+
+                similarity_tx
+                    .send_blocking(hashes)
+                    .expect("Failed to send perceptual hashes");
+            });
+
+            let hashed_icons = icons.clone();
+            glib::spawn_future_local(async move {
+                if let Ok(hashes) = similarity_rx.recv().await {
+                    for (index, hash) in hashes {
+                        hashed_icons[index].set_perceptual_hash(hash);
+                    }
+                }
+            });
+
             self.num_items.set(icons.len() as u32);
             self.obj().notify_num_items();
 
             let store = ListStore::new::<IconObject>();
             store.extend_from_slice(&icons);
 
-            let status_revealer = self.status_revealer.get();
             let progress_bar = self.progress.get();
-            let filter = self.filter.clone();
+            let obj = self.obj().clone();
             let filter_widget = self.filter_widget.get();
             glib::spawn_future_local(async move {
                 while let Ok((index, icon_index, aliases)) = alias_rx.recv().await {
@@ -265,87 +1330,42 @@ mod imp {
 
                     icon.add_aliases(aliases_names);
 
+                    let imp = obj.imp();
                     progress_bar.set_fraction((icon_index + 1) as f64 / icons.len() as f64);
-                    status_revealer.set_reveal_child(index != non_symlinks.len() - 1);
+                    imp.loading_status
+                        .set_visible(index != non_symlinks.len() - 1);
+                    imp.update_status();
 
-                    if index == non_symlinks.len() - 1
-                        && filter_widget.display_invalid_symlinks()
+                    if index != non_symlinks.len() - 1 {
+                        continue;
+                    }
+
+                    // Aliases are matched by the search, and invalid symlinks are only known now
+                    let alias_predicate = imp.predicates().iter().any(|predicate| {
+                        predicate.contains(&Predicate::HasAliases)
+                            || predicate.contains(&Predicate::Unlinked)
+                    });
+
+                    if alias_predicate
+                        || !imp.matcher.borrow().is_empty()
+                        || filter_widget.display_invalid_symlinks()
                     {
-                        filter.changed(gtk::FilterChange::Different);
+                        imp.refilter();
                     }
                 }
             });
 
             self.icons.replace(Some(store));
             self.obj().notify_icons();
+            self.filter_state.replace(self.filter_state());
 
             let obj = self.obj().clone();
             self.filter.set_filter_func(move |item| {
-                let search_text = obj.imp().search.text().to_string();
                 let icon = item
                     .downcast_ref::<IconObject>()
                     .expect("Needs to be an `IconObject`.");
 
-                let matcher = SkimMatcherV2::default();
-                let mut matches = matcher.fuzzy_match(&icon.name(), &search_text).is_some()
-                    || obj.include_tags_in_search()
-                        && matcher
-                            .fuzzy_match(&icon.tags().join(" "), &search_text)
-                            .is_some();
-
-                let filters: Vec<FilterFunction> = vec![
-                    Box::new(|icon: &IconObject, selector: &super::IconSelector| {
-                        let filter_widget = selector.imp().filter_widget.get();
-                        match filter_widget.symlink_filter_mode() {
-                            FilterMode::Is => icon.is_symlink(),
-                            FilterMode::Not => {
-                                filter_widget.display_invalid_symlinks() && !icon.has_target_index()
-                                    || !icon.is_symlink()
-                            }
-                            FilterMode::Either => true,
-                        }
-                    }),
-                    Box::new(|icon: &IconObject, selector: &super::IconSelector| {
-                        match selector.imp().filter_widget.embedded_filter_mode() {
-                            FilterMode::Is => icon.is_embedded(),
-                            FilterMode::Not => !icon.is_embedded(),
-                            FilterMode::Either => true,
-                        }
-                    }),
-                    Box::new(|icon: &IconObject, selector: &super::IconSelector| {
-                        match selector.imp().filter_widget.symbolic_filter_mode() {
-                            FilterMode::Is => icon.is_symbolic(),
-                            FilterMode::Not => !icon.is_symbolic(),
-                            FilterMode::Either => true,
-                        }
-                    }),
-                    Box::new(|icon: &IconObject, selector: &super::IconSelector| {
-                        selector
-                            .included_tags()
-                            .iter()
-                            .all(|tag| icon.tags().contains(tag))
-                    }),
-                    Box::new(|icon: &IconObject, selector: &super::IconSelector| {
-                        let included_categories =
-                            selector.imp().filter_widget.included_categories();
-
-                        let tags: Vec<_> =
-                            icon.tags().iter().map(|tag| tag.to_lowercase()).collect();
-
-                        tags.iter().enumerate().any(|(index, tag)| {
-                            included_categories
-                                .iter()
-                                .any(|c| tag.starts_with(c) && index != 0)
-                        }) || included_categories.contains(&String::from("unknown"))
-                            && tags
-                                .iter()
-                                .all(|tag| !CATEGORIES.iter().any(|c| tag.starts_with(c.1)))
-                    }),
-                ];
-
-                matches &= filters.iter().all(|f| f(icon, &obj));
-
-                matches
+                obj.imp().score(icon).is_some()
             });
 
             let filtered = gtk::FilterListModel::new(Some(self.icons()), Some(self.filter.clone()));
@@ -354,7 +1374,7 @@ mod imp {
             let obj = self.obj().clone();
             filtered.connect_pending_notify(move |_| handle_filter_pending(&obj));
 
-            let search_entry = self.search.get();
+            let obj = self.obj().clone();
             self.sorter.set_sort_func(move |a, b| {
                 let icon_a = a
                     .downcast_ref::<IconObject>()
@@ -363,19 +1383,17 @@ mod imp {
                     .downcast_ref::<IconObject>()
                     .expect("Needs to be an `IconObject`.");
 
-                let search_text = search_entry.text().to_string();
-                let matcher = SkimMatcherV2::default();
+                let imp = obj.imp();
+                let (data_a, data_b) = (icon_a.data(), icon_b.data());
 
-                let score_a = matcher
-                    .fuzzy_match(&icon_a.name(), &search_text)
-                    .unwrap_or(0);
-                let score_b = matcher
-                    .fuzzy_match(&icon_b.name(), &search_text)
-                    .unwrap_or(0);
+                let relevance = if imp.sort_by_relevance.get() {
+                    imp.score(icon_b).cmp(&imp.score(icon_a))
+                } else {
+                    std::cmp::Ordering::Equal
+                };
 
-                score_b
-                    .cmp(&score_a)
-                    .then_with(|| icon_a.name().cmp(&icon_b.name()))
+                relevance
+                    .then_with(|| imp.sort_order.get().compare(&data_a, &data_b))
                     .into()
             });
 
@@ -390,8 +1408,6 @@ mod imp {
                     .set_child(Some(&cell));
             });
 
-            let search = self.search.get();
-
             let displayed_icons = self.displayed_icons.clone();
             let obj = self.obj().clone();
             factory.connect_bind(move |_, list_item| {
@@ -409,7 +1425,7 @@ mod imp {
                     .and_downcast::<IconWidget>()
                     .expect("The child has to be a `IconWidget`.");
 
-                cell.bind(&icon, search.text().as_ref(), obj.icon_size());
+                cell.bind(&icon, &obj.imp().matcher.borrow(), obj.icon_size());
 
                 displayed_icons.borrow_mut().push(cell);
             });
@@ -429,12 +1445,13 @@ mod imp {
             });
 
             let target = self.obj().clone();
-            let selection = SingleSelection::builder().model(&sort).build();
-            let _ = selection
-                .bind_property("selected", &target, "selected")
-                .bidirectional()
-                .sync_create()
-                .build();
+            let selection = MultiSelection::new(Some(sort.clone()));
+
+            let obj = self.obj().clone();
+            selection.connect_selection_changed(move |_, _, _| obj.imp().update_selected());
+
+            let obj = self.obj().clone();
+            selection.connect_items_changed(move |_, _, _, _| obj.imp().update_selected());
 
             let scale = self.scale.get();
 
@@ -451,6 +1468,7 @@ mod imp {
 
             self.list.set_model(Some(&sort));
             self.view.set_model(Some(&selection));
+            self.update_selected();
             self.view.set_factory(Some(&factory));
             self.update_count_label();
         }
@@ -483,10 +1501,129 @@ impl IconSelector {
         glib::Object::builder().build()
     }
 
+    /// Returns the current search text and filters.
+    pub fn search_state(&self) -> SearchState {
+        let imp = self.imp();
+        let filter_widget = imp.filter_widget.get();
+
+        let formats = filter_widget.included_formats();
+        let formats = (formats.len() != FORMATS.len()).then_some(formats);
+        let size_directories = filter_widget
+            .filters_size_directories()
+            .then(|| filter_widget.included_size_directories());
+
+        SearchState {
+            text: imp.search.text().to_string(),
+            search_mode: self.search_mode(),
+            symlink: filter_widget.symlink_filter_mode(),
+            symbolic: filter_widget.symbolic_filter_mode(),
+            embedded: filter_widget.embedded_filter_mode(),
+            duplicate: filter_widget.duplicate_filter_mode(),
+            favorite: filter_widget.favorite_filter_mode(),
+            display_invalid_symlinks: filter_widget.display_invalid_symlinks(),
+            categories: filter_widget.included_categories(),
+            formats,
+            size_directories,
+            tags: self.tag_filter(),
+        }
+    }
+
+    /// Restores the search text and filters of a [SearchState].
+    pub fn apply_search_state(&self, state: &SearchState) {
+        let imp = self.imp();
+        let filter_widget = imp.filter_widget.get();
+
+        // Set first, the filter changes below add their terms to the text
+        imp.search.set_text(&state.text);
+
+        filter_widget.set_symlink_filter_mode(state.symlink);
+        filter_widget.set_symbolic_filter_mode(state.symbolic);
+        filter_widget.set_embedded_filter_mode(state.embedded);
+        filter_widget.set_duplicate_filter_mode(state.duplicate);
+        filter_widget.set_favorite_filter_mode(state.favorite);
+        filter_widget.set_display_invalid_symlinks(state.display_invalid_symlinks);
+        filter_widget.set_included_categories(state.categories.clone());
+        filter_widget.set_included_formats(state.formats.clone().unwrap_or_else(|| {
+            FORMATS.iter().map(|(_, value)| value.to_string()).collect()
+        }));
+        filter_widget.set_included_size_directories(
+            state
+                .size_directories
+                .clone()
+                .unwrap_or_else(|| filter_widget.size_directories()),
+        );
+
+        self.set_search_mode(state.search_mode);
+        self.set_tag_filter(state.tags.clone());
+    }
+
+    /// Changes the tag filter in place, updating the tag bar and the displayed icons.
+    pub fn update_tag_filter(&self, update: impl FnOnce(&mut TagFilter)) {
+        let mut filter = self.tag_filter();
+        update(&mut filter);
+        self.set_tag_filter(filter);
+    }
+
     pub fn selected_icon(&self) -> Option<IconObject> {
         self.imp().get_selected_icon()
     }
 
+    /// Returns every selected icon, in the order they are displayed.
+    pub fn selected_icons(&self) -> Vec<IconObject> {
+        self.imp().selected_icons()
+    }
+
+    /// Returns the icons that match the current search and filters, in the order they are
+    /// displayed.
+    pub fn filtered_icons(&self) -> Vec<IconObject> {
+        self.imp().filtered_icons()
+    }
+
+    /// Exports the icons as PNGs in the background, replacing any export still running.
+    pub fn export_icons(&self, names: Vec<String>, export: BatchExport) {
+        self.imp().export_icons(names, export);
+    }
+
+    /// Shows the outcome of an export that ran elsewhere in the status bar.
+    pub fn show_export_result(&self, message: &str) {
+        self.imp().show_export_result(message);
+    }
+
+    /// Returns a snapshot of the data of every icon in the theme.
+    pub fn icon_data(&self) -> Vec<IconData> {
+        self.icons()
+            .map(|store| {
+                store
+                    .iter::<IconObject>()
+                    .filter_map(Result::ok)
+                    .map(IconData::from)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Returns the icons that look the most like the given icon, regardless of their names.
+    ///
+    /// Icons are compared by their perceptual hash, which is computed in the background
+    /// after the theme is loaded, so this returns nothing until that is done.
+    pub fn similar_icons(&self, icon: &IconObject, limit: usize) -> Vec<IconObject> {
+        let (Some(target), Some(store)) = (icon.perceptual_hash(), self.icons()) else {
+            return Vec::new();
+        };
+
+        let icons: Vec<IconObject> = store.iter().filter_map(Result::ok).collect();
+        let hashes = icons
+            .iter()
+            .enumerate()
+            .filter(|(_, other)| *other != icon)
+            .filter_map(|(index, other)| other.perceptual_hash().map(|hash| (index, hash)));
+
+        nearest_hashes(target, hashes, limit)
+            .into_iter()
+            .map(|(index, _)| icons[index].clone())
+            .collect()
+    }
+
     pub fn connect_activate<F>(&self, f: F)
     where
         F: Fn(&gtk::GridView, u32) + 'static,
@@ -0,0 +1,96 @@
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+
+use super::load_config;
+
+const SNIPPETS_FILE: &str = "snippets.toml";
+const BUNDLED_SNIPPETS: &str = include_str!("../data/snippets.toml");
+
+/// A template for code that uses an icon, see [Snippet::expand].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snippet {
+    /// The name of the toolkit or format, shown in the "Copy as…" menu.
+    pub label: String,
+    pub template: String,
+}
+
+impl Snippet {
+    /// Replaces `{name}` in the template with the icon name.
+    pub fn expand(&self, icon_name: &str) -> String {
+        self.template.replace("{name}", icon_name)
+    }
+}
+
+/// The snippets icons can be copied as, in the order they're listed.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snippets {
+    #[serde(default, rename = "snippet")]
+    pub snippets: Vec<Snippet>,
+}
+
+impl Snippets {
+    /// The snippets shipped with the application.
+    pub fn bundled() -> Self {
+        Self::parse(BUNDLED_SNIPPETS).expect("Bundled snippets are invalid")
+    }
+
+    /// Loads the bundled snippets, extended with the user's snippets file if it exists.
+    pub fn load() -> Result<Self> {
+        let mut snippets = Self::bundled();
+
+        if let Some(user_snippets) = load_config(SNIPPETS_FILE)? {
+            snippets.merge(user_snippets);
+        }
+
+        Ok(snippets)
+    }
+
+    pub fn parse(source: &str) -> Result<Self> {
+        Ok(toml::from_str(source)?)
+    }
+
+    /// Appends the snippets of another list, replacing the snippets with the same label.
+    pub fn merge(&mut self, other: Snippets) {
+        for snippet in other.snippets {
+            match self.snippets.iter_mut().find(|s| s.label == snippet.label) {
+                Some(existing) => *existing = snippet,
+                None => self.snippets.push(snippet),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge() {
+        let mut snippets = Snippets::bundled();
+        let count = snippets.snippets.len();
+        assert_eq!(
+            snippets.snippets[0].expand("edit-copy"),
+            "gtk::Image::from_icon_name(\"edit-copy\")"
+        );
+
+        snippets.merge(
+            Snippets::parse(
+                r#"
+                [[snippet]]
+                label = "CSS"
+                template = "url(\"{name}.svg\")"
+
+                [[snippet]]
+                label = "Vala"
+                template = "new Gtk.Image.from_icon_name (\"{name}\")"
+                "#,
+            )
+            .unwrap(),
+        );
+
+        assert_eq!(snippets.snippets.len(), count + 1);
+        let css = snippets.snippets.iter().find(|s| s.label == "CSS").unwrap();
+        assert_eq!(css.expand("a"), "url(\"a.svg\")");
+        assert_eq!(snippets.snippets.last().unwrap().label, "Vala");
+    }
+}
@@ -0,0 +1,177 @@
+use std::cmp::Ordering;
+
+use gtk::glib;
+
+use super::{icon::IconData, icon_context};
+
+/// The order icons are displayed in, see [SortOrder::compare].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Default, glib::Enum)]
+#[enum_type(name = "NettIconViewerSortOrder")]
+pub enum SortOrder {
+    #[default]
+    Name,
+    Theme,
+    Context,
+    FileSize,
+    AliasCount,
+    Path,
+    SymbolicFirst,
+}
+
+impl SortOrder {
+    /// All orders, in the order they are listed in the selector.
+    pub const ALL: [SortOrder; 7] = [
+        SortOrder::Name,
+        SortOrder::Theme,
+        SortOrder::Context,
+        SortOrder::FileSize,
+        SortOrder::AliasCount,
+        SortOrder::Path,
+        SortOrder::SymbolicFirst,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SortOrder::Name => "Name",
+            SortOrder::Theme => "Theme",
+            SortOrder::Context => "Context",
+            SortOrder::FileSize => "File Size",
+            SortOrder::AliasCount => "Alias Count",
+            SortOrder::Path => "Path",
+            SortOrder::SymbolicFirst => "Symbolic First",
+        }
+    }
+
+    /// Compares two icons, falling back to their names if they are equal in this order.
+    ///
+    /// File sizes and alias counts are sorted largest first, icons missing the
+    /// compared value are sorted last.
+    pub fn compare(&self, a: &IconData, b: &IconData) -> Ordering {
+        let ordering = match self {
+            SortOrder::Name => Ordering::Equal,
+            SortOrder::Theme => none_last(a.tags.first(), b.tags.first(), |a, b| natural_cmp(a, b)),
+            SortOrder::Context => none_last(icon_context(a), icon_context(b), str::cmp),
+            SortOrder::FileSize => none_last(a.file_size, b.file_size, |a, b| b.cmp(&a)),
+            SortOrder::AliasCount => b.aliases.len().cmp(&a.aliases.len()),
+            SortOrder::Path => none_last(a.path.as_ref(), b.path.as_ref(), |a, b| {
+                natural_cmp(&a.to_string_lossy(), &b.to_string_lossy())
+            }),
+            SortOrder::SymbolicFirst => b.is_symbolic.cmp(&a.is_symbolic),
+        };
+
+        ordering.then_with(|| natural_cmp(&a.name, &b.name))
+    }
+}
+
+fn none_last<T>(a: Option<T>, b: Option<T>, cmp: impl Fn(T, T) -> Ordering) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => cmp(a, b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+/// Compares strings ignoring case, with runs of digits compared by their numeric value.
+///
+/// This sorts `view-grid-2` before `view-grid-10`.
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a_chunks = chunks(a);
+    let mut b_chunks = chunks(b);
+
+    loop {
+        let ordering = match (a_chunks.next(), b_chunks.next()) {
+            (Some(a), Some(b)) => match (a.as_bytes()[0], b.as_bytes()[0]) {
+                (x, y) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                    let a = a.trim_start_matches('0');
+                    let b = b.trim_start_matches('0');
+                    a.len().cmp(&b.len()).then_with(|| a.cmp(b))
+                }
+                _ => a.to_lowercase().cmp(&b.to_lowercase()),
+            },
+            (Some(_), None) => Ordering::Greater,
+            (None, Some(_)) => Ordering::Less,
+            (None, None) => return a.cmp(b),
+        };
+
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+/// Splits the text into alternating runs of digits and non-digits.
+fn chunks(text: &str) -> impl Iterator<Item = &str> {
+    let mut rest = text;
+
+    std::iter::from_fn(move || {
+        let first = rest.chars().next()?;
+        let end = rest
+            .find(|c: char| c.is_ascii_digit() != first.is_ascii_digit())
+            .unwrap_or(rest.len());
+
+        let (chunk, remaining) = rest.split_at(end);
+        rest = remaining;
+        Some(chunk)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    #[test]
+    fn test_natural_cmp() {
+        let mut names = vec![
+            "view-grid-10",
+            "View-grid-2",
+            "view-grid",
+            "view-grid-02a",
+            "view-grid-1",
+        ];
+        names.sort_by(|a, b| natural_cmp(a, b));
+
+        assert_eq!(
+            names,
+            vec![
+                "view-grid",
+                "view-grid-1",
+                "View-grid-2",
+                "view-grid-02a",
+                "view-grid-10"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compare() {
+        let icon = |name: &str, theme: &str, size: Option<u64>, symbolic: bool| IconData {
+            name: name.to_string(),
+            tags: vec![theme.to_string(), String::from("apps")],
+            path: Some(PathBuf::from(format!(
+                "/usr/share/icons/{theme}/{name}.svg"
+            ))),
+            file_size: size,
+            is_symbolic: symbolic,
+            ..Default::default()
+        };
+
+        let mut icons = [
+            icon("b", "Adwaita", Some(10), true),
+            icon("c", "hicolor", None, false),
+            icon("a", "hicolor", Some(20), false),
+        ];
+
+        let mut sorted = |order: SortOrder| {
+            icons.sort_by(|a, b| order.compare(a, b));
+            icons.iter().map(|i| i.name.as_str()).collect::<String>()
+        };
+
+        assert_eq!(sorted(SortOrder::Name), "abc");
+        assert_eq!(sorted(SortOrder::Theme), "bac");
+        assert_eq!(sorted(SortOrder::FileSize), "abc");
+        assert_eq!(sorted(SortOrder::SymbolicFirst), "bac");
+    }
+}
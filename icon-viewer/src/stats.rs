@@ -0,0 +1,199 @@
+use std::collections::BTreeMap;
+
+use super::{CATEGORIES, icon::IconData};
+
+const LARGEST_FILE_COUNT: usize = 10;
+
+/// Figures about a loaded icon theme, shown in the statistics view.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ThemeStatistics {
+    pub total: usize,
+    pub symlinks: usize,
+    pub broken_symlinks: usize,
+    pub symbolic: usize,
+    pub embedded: usize,
+    pub scalable: usize,
+    /// Icon count per context, using the names from [CATEGORIES].
+    pub contexts: Vec<(String, usize)>,
+    pub size_directories: Vec<(String, usize)>,
+    pub formats: Vec<(String, usize)>,
+    /// Combined size of all icon files, not counting symlinks.
+    pub disk_usage: u64,
+    pub largest_files: Vec<(String, u64)>,
+}
+
+impl ThemeStatistics {
+    /// Collects statistics for the given icons.
+    ///
+    /// This reads the metadata of every icon file, so it should not be called on the main thread.
+    pub fn collect(icons: &[IconData]) -> Self {
+        let mut stats = ThemeStatistics {
+            total: icons.len(),
+            ..Default::default()
+        };
+
+        let mut contexts = BTreeMap::new();
+        let mut size_directories = BTreeMap::new();
+        let mut formats = BTreeMap::new();
+        let mut files = Vec::new();
+
+        for icon in icons {
+            stats.symbolic += icon.is_symbolic as usize;
+            stats.embedded += icon.is_embedded as usize;
+
+            if icon.is_symlink {
+                stats.symlinks += 1;
+
+                // The target index is only known once aliases are resolved, and not at all
+                // for targets outside the theme, so only the file system tells if it's broken
+                if !icon.path.as_ref().is_some_and(|path| path.exists()) {
+                    stats.broken_symlinks += 1;
+                }
+            }
+
+            *contexts.entry(icon_context(icon)).or_insert(0) += 1;
+
+            let format = icon_format(icon);
+            if format == "SVG" {
+                stats.scalable += 1;
+            }
+            *formats.entry(format).or_insert(0) += 1;
+
+            let size_directory = icon.size_directory();
+            *size_directories.entry(size_directory).or_insert(0) += 1;
+
+            if icon.is_symlink {
+                continue;
+            }
+
+            if let Some(size) = icon
+                .path
+                .as_ref()
+                .and_then(|path| std::fs::metadata(path).ok())
+                .map(|metadata| metadata.len())
+            {
+                stats.disk_usage += size;
+                files.push((icon.name.clone(), size));
+            }
+        }
+
+        files.sort_by(|(a_name, a), (b_name, b)| b.cmp(a).then_with(|| a_name.cmp(b_name)));
+        files.truncate(LARGEST_FILE_COUNT);
+
+        stats.contexts = none_last(contexts)
+            .into_iter()
+            .map(|(context, count)| (context.unwrap_or("Unknown").to_string(), count))
+            .collect();
+        stats.size_directories = none_last(size_directories)
+            .into_iter()
+            .map(|(directory, count)| {
+                let name = directory
+                    .map(|d| d.to_string())
+                    .unwrap_or_else(|| String::from("None"));
+
+                (name, count)
+            })
+            .collect();
+        stats.formats = formats
+            .into_iter()
+            .map(|(format, count)| (format.to_string(), count))
+            .collect();
+        stats.largest_files = files;
+
+        stats
+    }
+}
+
+/// Returns the name of the context the icon is in, based on its tags.
+pub fn icon_context(icon: &IconData) -> Option<&'static str> {
+    icon.tags
+        .iter()
+        .skip(1)
+        .map(|tag| tag.to_lowercase())
+        .find_map(|tag| CATEGORIES.iter().find(|c| tag.starts_with(c.1)))
+        .map(|(name, _)| *name)
+}
+
+/// Returns the display name of the file format of the icon.
+pub fn icon_format(icon: &IconData) -> &'static str {
+    if icon.is_embedded {
+        return "Embedded";
+    }
+
+    match icon
+        .path
+        .as_ref()
+        .and_then(|path| path.extension())
+        .and_then(|ext| ext.to_str())
+    {
+        Some("svg") => "SVG",
+        Some("png") => "PNG",
+        Some("xpm") => "XPM",
+        _ => "Other",
+    }
+}
+
+/// Moves the entry without a key to the end, it's sorted first otherwise.
+fn none_last<K, V>(map: BTreeMap<Option<K>, V>) -> Vec<(Option<K>, V)> {
+    let mut entries: Vec<_> = map.into_iter().collect();
+    if entries.first().is_some_and(|(key, _)| key.is_none()) {
+        entries.rotate_left(1);
+    }
+
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect() {
+        let icons = vec![
+            IconData::at_path("a", "/usr/share/icons/Test/16x16/apps/a.png"),
+            IconData::at_path("b", "/usr/share/icons/Test/16x16/actions/b.png"),
+            IconData::at_path("c", "/usr/share/icons/Test/scalable/apps/c.svg"),
+            IconData {
+                name: "d".to_string(),
+                is_embedded: true,
+                ..Default::default()
+            },
+            IconData {
+                is_symlink: true,
+                ..IconData::at_path("e", "/usr/share/icons/Test/scalable/apps/e.svg")
+            },
+        ];
+
+        let stats = ThemeStatistics::collect(&icons);
+
+        assert_eq!(stats.total, 5);
+        assert_eq!(stats.symlinks, 1);
+        assert_eq!(stats.broken_symlinks, 1);
+        assert_eq!(stats.embedded, 1);
+        assert_eq!(stats.scalable, 2);
+        assert_eq!(
+            stats.contexts,
+            vec![
+                ("Actions".to_string(), 1),
+                ("Applications".to_string(), 3),
+                ("Unknown".to_string(), 1),
+            ]
+        );
+        assert_eq!(
+            stats.size_directories,
+            vec![
+                ("16x16".to_string(), 2),
+                ("scalable".to_string(), 2),
+                ("None".to_string(), 1),
+            ]
+        );
+        assert_eq!(
+            stats.formats,
+            vec![
+                ("Embedded".to_string(), 1),
+                ("PNG".to_string(), 2),
+                ("SVG".to_string(), 2),
+            ]
+        );
+    }
+}
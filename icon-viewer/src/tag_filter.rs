@@ -0,0 +1,122 @@
+use gtk::glib;
+use serde::{Deserialize, Serialize};
+
+use super::Predicate;
+
+/// Whether an icon needs all of the included tags, or just one of them.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TagMatchMode {
+    #[default]
+    All,
+    Any,
+}
+
+/// Tags an icon has to have, or must not have, to be displayed.
+#[derive(Clone, Debug, Default, PartialEq, Eq, glib::Boxed, Serialize, Deserialize)]
+#[boxed_type(name = "NettIconViewerTagFilter")]
+pub struct TagFilter {
+    #[serde(default)]
+    pub included: Vec<String>,
+    #[serde(default)]
+    pub excluded: Vec<String>,
+    #[serde(default)]
+    pub mode: TagMatchMode,
+}
+
+impl TagFilter {
+    pub fn is_empty(&self) -> bool {
+        self.included.is_empty() && self.excluded.is_empty()
+    }
+
+    /// Includes the tag, unless it's already part of the filter.
+    pub fn add(&mut self, tag: &str) {
+        if !self.contains(tag) {
+            self.included.push(tag.to_string());
+        }
+    }
+
+    pub fn remove(&mut self, tag: &str) {
+        self.included.retain(|t| t != tag);
+        self.excluded.retain(|t| t != tag);
+    }
+
+    pub fn contains(&self, tag: &str) -> bool {
+        self.included.iter().chain(&self.excluded).any(|t| t == tag)
+    }
+
+    pub fn is_excluded(&self, tag: &str) -> bool {
+        self.excluded.iter().any(|t| t == tag)
+    }
+
+    /// Moves an included tag to the excluded ones and vice versa.
+    pub fn toggle(&mut self, tag: &str) {
+        if self.is_excluded(tag) {
+            self.excluded.retain(|t| t != tag);
+            self.included.push(tag.to_string());
+        } else if self.included.iter().any(|t| t == tag) {
+            self.included.retain(|t| t != tag);
+            self.excluded.push(tag.to_string());
+        }
+    }
+
+    pub fn predicates(&self) -> Vec<Predicate> {
+        let included = self
+            .included
+            .iter()
+            .map(|tag| Predicate::Tag(tag.to_lowercase()));
+
+        let mut predicates: Vec<_> = match self.mode {
+            TagMatchMode::All => included.collect(),
+            TagMatchMode::Any if self.included.is_empty() => Vec::new(),
+            TagMatchMode::Any => vec![Predicate::Any(included.collect())],
+        };
+
+        predicates.extend(
+            self.excluded
+                .iter()
+                .map(|tag| Predicate::Not(Box::new(Predicate::Tag(tag.to_lowercase())))),
+        );
+
+        predicates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::icon::IconData;
+
+    #[test]
+    fn test_predicates() {
+        let icon = |tags: &[&str]| IconData {
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            path: Some(PathBuf::new()),
+            ..Default::default()
+        };
+        let matches = |filter: &TagFilter, icon: &IconData| {
+            filter.predicates().iter().all(|p| p.matches(icon))
+        };
+
+        let adwaita = icon(&["Adwaita", "16x16", "apps"]);
+        let hicolor = icon(&["hicolor", "scalable", "apps"]);
+
+        let mut filter = TagFilter::default();
+        filter.add("Adwaita");
+        filter.add("hicolor");
+        assert!(!matches(&filter, &adwaita));
+
+        filter.mode = TagMatchMode::Any;
+        assert!(matches(&filter, &adwaita) && matches(&filter, &hicolor));
+
+        filter.toggle("hicolor");
+        assert_eq!(filter.excluded, vec![String::from("hicolor")]);
+        assert!(matches(&filter, &adwaita) && !matches(&filter, &hicolor));
+
+        filter.remove("Adwaita");
+        filter.remove("hicolor");
+        assert!(filter.is_empty() && filter.predicates().is_empty());
+    }
+}